@@ -1,12 +1,13 @@
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use uuid::Uuid;
 
 use crate::config::Config;
 use crate::genotype::{Genotype, ReasoningStrategy};
-use crate::llm::LlmClient;
+use crate::llm::{DeltaSink, LlmClient};
 use anyhow::Result;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
     pub id: Uuid,
     pub genotype: Genotype,
@@ -25,21 +26,37 @@ impl Agent {
         problem: &str,
         llm: &LlmClient,
         config: &Config,
+        on_delta: Option<&DeltaSink<'_>>,
     ) -> Result<AgentOutput> {
         let system_prompt = self.genotype.build_system_prompt();
         let start = std::time::Instant::now();
 
-        let response = llm
-            .chat_completion(
-                &system_prompt,
-                problem,
-                self.genotype.temperature,
-                self.genotype.top_p,
-                config.max_tokens,
-            )
-            .await?;
+        let response = match on_delta {
+            Some(sink) => {
+                llm.chat_completion_streaming(
+                    &system_prompt,
+                    problem,
+                    self.genotype.temperature,
+                    self.genotype.top_p,
+                    config.max_tokens,
+                    sink,
+                )
+                .await?
+            }
+            None => {
+                llm.chat_completion(
+                    &system_prompt,
+                    problem,
+                    self.genotype.temperature,
+                    self.genotype.top_p,
+                    config.max_tokens,
+                )
+                .await?
+            }
+        };
 
         let elapsed = start.elapsed();
+        let cost = llm.cost_of(&response);
 
         Ok(AgentOutput {
             agent_id: self.id,
@@ -47,17 +64,24 @@ impl Agent {
             strategy: self.genotype.strategy.clone(),
             content: response.content,
             tokens_used: response.total_tokens,
+            prompt_tokens: response.prompt_tokens,
+            completion_tokens: response.completion_tokens,
+            cost,
             elapsed,
         })
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentOutput {
     pub agent_id: Uuid,
     pub genotype_name: String,
     pub strategy: ReasoningStrategy,
     pub content: String,
     pub tokens_used: u32,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    /// USD, or 0.0 when the model has no known price.
+    pub cost: f64,
     pub elapsed: Duration,
 }
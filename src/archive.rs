@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::evolution::ScoredTeam;
+use crate::genotype::ReasoningStrategy;
+use crate::orchestrator::FitnessScore;
+use crate::team::{Team, TeamOutput};
+
+/// Chance that a crossover parent is drawn from the archive: off unless asked.
+pub const DEFAULT_ARCHIVE_PARENT_RATE: f64 = 0.0;
+
+/// Mean words per agent reply below which an answer counts as short.
+const SHORT_ANSWER_WORDS: usize = 120;
+/// Mean words per agent reply from which an answer counts as long.
+const LONG_ANSWER_WORDS: usize = 300;
+/// Mean agent temperature below which a team counts as cool.
+const LOW_TEMPERATURE: f64 = 0.5;
+/// Mean agent temperature from which a team counts as hot.
+const HIGH_TEMPERATURE: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthBand {
+    Short,
+    Medium,
+    Long,
+}
+
+impl fmt::Display for LengthBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LengthBand::Short => write!(f, "short"),
+            LengthBand::Medium => write!(f, "medium"),
+            LengthBand::Long => write!(f, "long"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureBand {
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for TemperatureBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemperatureBand::Low => write!(f, "low"),
+            TemperatureBand::Medium => write!(f, "medium"),
+            TemperatureBand::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Behaviour niche of a scored team: one cell of the MAP-Elites grid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Niche {
+    /// Mean length of the agents' replies.
+    pub length: LengthBand,
+    /// Most common strategy among the non-red-team agents.
+    pub strategy: ReasoningStrategy,
+    /// Mean agent temperature.
+    pub temperature: TemperatureBand,
+}
+
+impl Niche {
+    pub fn of(team: &Team, output: &TeamOutput) -> Self {
+        Self {
+            length: length_band(output),
+            strategy: dominant_strategy(team),
+            temperature: temperature_band(team),
+        }
+    }
+}
+
+impl fmt::Display for Niche {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}, {}", self.length, self.strategy, self.temperature)
+    }
+}
+
+/// Best team found so far for one niche.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveCell {
+    pub niche: Niche,
+    pub team: Team,
+    pub output: TeamOutput,
+    pub score: FitnessScore,
+}
+
+/// MAP-Elites archive: the best-scoring team of every behaviour niche seen
+/// during a run, kept alongside the population. Cells are in order of
+/// discovery.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EliteArchive {
+    pub cells: Vec<ArchiveCell>,
+}
+
+impl EliteArchive {
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Files every team of `scored` under its niche, replacing a cell's team
+    /// only when the newcomer has a strictly higher total. Returns how many
+    /// cells were filled or improved.
+    pub fn insert(&mut self, scored: &[ScoredTeam]) -> usize {
+        let mut changed = 0;
+        for st in scored {
+            let niche = Niche::of(&st.team, &st.output);
+            let cell = ArchiveCell {
+                niche: niche.clone(),
+                team: st.team.clone(),
+                output: st.output.clone(),
+                score: st.score.clone(),
+            };
+            match self.cells.iter_mut().find(|c| c.niche == niche) {
+                Some(existing) if existing.score.total >= st.score.total => continue,
+                Some(existing) => *existing = cell,
+                None => self.cells.push(cell),
+            }
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Cells sorted by total score, best first.
+    pub fn ranked(&self) -> Vec<&ArchiveCell> {
+        let mut cells: Vec<&ArchiveCell> = self.cells.iter().collect();
+        cells.sort_by(|a, b| b.score.total.total_cmp(&a.score.total));
+        cells
+    }
+}
+
+fn length_band(output: &TeamOutput) -> LengthBand {
+    let replies = output.agent_outputs.len().max(1);
+    let words: usize = output
+        .agent_outputs
+        .iter()
+        .map(|o| o.content.split_whitespace().count())
+        .sum();
+    match words / replies {
+        w if w < SHORT_ANSWER_WORDS => LengthBand::Short,
+        w if w < LONG_ANSWER_WORDS => LengthBand::Medium,
+        _ => LengthBand::Long,
+    }
+}
+
+/// Most common strategy among the team's regular agents; ties go to the
+/// strategy that appears first. All-red-team teams count as red team.
+fn dominant_strategy(team: &Team) -> ReasoningStrategy {
+    let regular: Vec<&ReasoningStrategy> = team
+        .agents
+        .iter()
+        .filter(|a| !a.genotype.is_red_team)
+        .map(|a| &a.genotype.strategy)
+        .collect();
+    let mut counts: HashMap<&ReasoningStrategy, usize> = HashMap::new();
+    for strategy in &regular {
+        *counts.entry(strategy).or_default() += 1;
+    }
+    let mut best: Option<(&ReasoningStrategy, usize)> = None;
+    for strategy in regular {
+        let count = counts[strategy];
+        if best.is_none_or(|(_, n)| count > n) {
+            best = Some((strategy, count));
+        }
+    }
+    best.map_or(ReasoningStrategy::RedTeam, |(s, _)| s.clone())
+}
+
+fn temperature_band(team: &Team) -> TemperatureBand {
+    let agents = team.agents.len().max(1) as f64;
+    let mean = team.agents.iter().map(|a| a.genotype.temperature).sum::<f64>() / agents;
+    if mean < LOW_TEMPERATURE {
+        TemperatureBand::Low
+    } else if mean < HIGH_TEMPERATURE {
+        TemperatureBand::Medium
+    } else {
+        TemperatureBand::High
+    }
+}
@@ -1,18 +1,32 @@
-use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
 use colored::*;
 use futures::stream::{self, StreamExt};
-use rand::rngs::StdRng;
 use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
+use uuid::Uuid;
 
+use crate::agent::Agent;
+use crate::archive::EliteArchive;
+use crate::checkpoint::Checkpoint;
 use crate::config::Config;
-use crate::evolution::{self, ScoredTeam};
+use crate::evolution::{self, Migration, ParetoMember, ScoredTeam};
 use crate::knowledge::KnowledgeBase;
-use crate::llm::LlmClient;
+use crate::llm::{Cassette, LlmClient, RateLimiter, ResponseCache};
 use crate::orchestrator::{ConclusionHistory, FitnessScore, Orchestrator};
-use crate::team::{Team, TeamOutput};
+use crate::species;
+use crate::team::{AgentSink, Team, TeamOutput};
 
-#[derive(Debug, Clone)]
+/// How often in-flight phases re-check the run's budgets.
+const BUDGET_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamScore {
     pub name: String,
     pub total: f64,
@@ -20,9 +34,11 @@ pub struct TeamScore {
     pub consistency: f64,
     pub efficiency: f64,
     pub diversity_penalty: f64,
+    #[serde(default)]
+    pub judge_critique: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Phase {
     Initialising,
     AgentsWorking,
@@ -45,14 +61,63 @@ impl std::fmt::Display for Phase {
     }
 }
 
-#[derive(Debug, Clone)]
+/// A resource cap that can end a run early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Budget {
+    Tokens,
+    Duration,
+    Cost,
+}
+
+impl std::fmt::Display for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Budget::Tokens => write!(f, "token"),
+            Budget::Duration => write!(f, "time"),
+            Budget::Cost => write!(f, "cost"),
+        }
+    }
+}
+
+/// Why evolution stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopReason {
+    MaxGenerations,
+    Converged,
+    BudgetExhausted(Budget),
+    /// [`RunControl::stop`] ended evolution early.
+    Stopped,
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopReason::MaxGenerations => write!(f, "generation limit reached"),
+            StopReason::Converged => write!(f, "converged"),
+            StopReason::BudgetExhausted(budget) => write!(f, "{budget} budget exhausted"),
+            StopReason::Stopped => write!(f, "stopped early"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IslandScore {
+    pub island: usize,
+    pub teams: usize,
+    pub best_name: String,
+    pub best_score: f64,
+    pub mean_score: f64,
+    pub mutation_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct TeamDetail {
     pub name: String,
     pub agents: Vec<TeamAgentDetail>,
     pub total_tokens: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TeamAgentDetail {
     pub name: String,
     pub strategy: String,
@@ -60,7 +125,11 @@ pub struct TeamAgentDetail {
     pub is_red_team: bool,
 }
 
-#[derive(Debug)]
+/// Progress of a run. Serialises as `{"event": "<snake_case name>", "data": ...}`,
+/// the line format of `--output jsonl`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ArenaEvent {
     GenerationStarted { gen: usize, total: usize },
     PhaseChanged(Phase),
@@ -69,24 +138,192 @@ pub enum ArenaEvent {
         scores: Vec<TeamScore>,
         best_name: String,
         best_score: f64,
+        /// Teams per species, numbered from the best team's species.
+        species: Vec<usize>,
+    },
+    /// Running usage; cache counts are zero when the response cache is off.
+    TokenUpdate {
+        total_tokens: u64,
+        total_cost: f64,
+        cache_hits: u64,
+        cache_misses: u64,
+    },
+    /// A fragment of an agent's reply; only sent when [`Config::stream`] is set.
+    AgentChunk {
+        team: String,
+        /// Tells apart agents that share a genotype name.
+        agent_id: Uuid,
+        agent: String,
+        delta: String,
     },
-    TokenUpdate { total_tokens: u64 },
     TeamDetails(Vec<TeamDetail>),
+    /// How each island did in generation `gen`; only sent with `--islands`.
+    IslandScores {
+        gen: usize,
+        islands: Vec<IslandScore>,
+    },
     Evolving { kept: usize, spawning: usize },
+    /// Teams copied between islands while breeding generation `gen`.
+    Migrated {
+        gen: usize,
+        migrations: Vec<Migration>,
+    },
     Converged { gen: usize, score: f64 },
+    /// Written after generation `gen`; resume with `emas resume <path>`.
+    CheckpointSaved { gen: usize, path: PathBuf },
+    /// A budget ran out during generation `gen`; in-flight work was cancelled.
+    BudgetExhausted {
+        gen: usize,
+        budget: Budget,
+        detail: String,
+    },
+    /// The run is held before generation `gen` continues; see [`RunControl::pause`].
+    Paused { gen: usize },
+    Resumed,
+    /// [`RunControl::stop`] ended evolution in generation `gen`.
+    Stopped { gen: usize },
     Warning(String),
     SynthesisStarted,
     Completed(EvolutionResult),
     Error(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvolutionResult {
     pub best_team: Team,
     pub best_output: TeamOutput,
     pub best_score: FitnessScore,
     pub synthesis: String,
     pub generations_run: usize,
+    /// USD across agents, judges and synthesis; 0.0 when no model is priced.
+    pub total_cost: f64,
+    pub stop_reason: StopReason,
+    /// Pass as `--seed` to replay the same evolutionary choices.
+    pub seed: u64,
+    /// Every team's score, one list per evaluated generation.
+    pub generation_scores: Vec<Vec<TeamScore>>,
+    /// Tokens used by agents, judges and synthesis, including before a resume.
+    #[serde(default)]
+    pub total_tokens: u64,
+    /// Every team no other scored team beat on quality, consistency and
+    /// efficiency at once, best total first.
+    #[serde(default)]
+    pub pareto_front: Vec<ParetoMember>,
+    /// Best team of every behaviour niche reached during the run.
+    #[serde(default)]
+    pub archive: EliteArchive,
+}
+
+/// Tokens, USD and wall-clock time consumed by a run.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Usage {
+    pub tokens: u64,
+    pub cost: f64,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ControlState {
+    Running,
+    Paused,
+    Stopped,
+}
+
+/// Pauses, resumes or stops a run from another task. Clones share one state.
+///
+/// The arena honours a pause between the agent, judge and evolve phases.
+/// A stop ends evolution at the next boundary, or mid-phase once a team has
+/// been scored, and the best team so far is synthesised as usual.
+#[derive(Debug, Clone)]
+pub struct RunControl {
+    state: Arc<watch::Sender<ControlState>>,
+}
+
+impl Default for RunControl {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(watch::channel(ControlState::Running).0),
+        }
+    }
+}
+
+impl RunControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pause(&self) {
+        self.transition(ControlState::Running, ControlState::Paused);
+    }
+
+    pub fn resume(&self) {
+        self.transition(ControlState::Paused, ControlState::Running);
+    }
+
+    /// Also lifts a pause. Cannot be undone.
+    pub fn stop(&self) {
+        self.state.send_replace(ControlState::Stopped);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.state.borrow() == ControlState::Paused
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        *self.state.borrow() == ControlState::Stopped
+    }
+
+    fn transition(&self, from: ControlState, to: ControlState) {
+        self.state.send_if_modified(|state| {
+            let change = *state == from;
+            if change {
+                *state = to;
+            }
+            change
+        });
+    }
+
+    async fn wait_until(&self, done: impl FnMut(&ControlState) -> bool) {
+        // The sender lives in `self`, so the channel never closes here.
+        let _ = self.state.subscribe().wait_for(done).await;
+    }
+}
+
+/// Why an in-flight phase was cut short.
+enum Interrupt {
+    Budget(Budget),
+    Stopped,
+}
+
+/// Everything the evolution loop carries from one generation to the next.
+/// Checkpoints persist it so a run can resume exactly where it stopped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunState {
+    /// Zero-based index of the next generation to run.
+    pub next_gen: usize,
+    pub population: Vec<Team>,
+    pub elite_cache: Vec<(Team, TeamOutput, FitnessScore)>,
+    pub knowledge: KnowledgeBase,
+    pub conclusion_history: ConclusionHistory,
+    pub best_ever: Option<(Team, TeamOutput, FitnessScore)>,
+    /// Scores of every generation evaluated so far.
+    #[serde(default)]
+    pub generation_scores: Vec<Vec<TeamScore>>,
+    /// Non-dominated teams over the whole run so far.
+    #[serde(default)]
+    pub pareto_front: Vec<ParetoMember>,
+    /// MAP-Elites archive over the whole run so far.
+    #[serde(default)]
+    pub archive: EliteArchive,
+    pub converged: bool,
+    pub rng: ChaCha8Rng,
+}
+
+/// Start of this process's share of the run plus usage carried in from a
+/// checkpoint.
+struct RunClock {
+    started: Instant,
+    prior: Usage,
 }
 
 pub struct Arena {
@@ -97,19 +334,62 @@ pub struct Arena {
 }
 
 impl Arena {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config) -> Result<Self> {
+        let limiter = Arc::new(RateLimiter::new(
+            config.max_concurrency,
+            config.rpm,
+            config.tpm,
+        ));
+        Self::with_limiter(config, limiter)
+    }
+
+    /// Like [`new`](Self::new) but draws on `limiter` instead of building one
+    /// from the config, so concurrent runs against one provider can share
+    /// its request and token budgets.
+    pub fn with_limiter(mut config: Config, limiter: Arc<RateLimiter>) -> Result<Self> {
+        // Only a seed the user chose is sent to the provider. A random one
+        // would be part of every cache key, so unseeded reruns never hit.
+        let request_seed = config.seed;
+        config.seed.get_or_insert_with(rand::random);
+        let cache = config.cache_dir.as_ref().map(|dir| {
+            Arc::new(ResponseCache::new(dir).deterministic_only(config.cache_deterministic_only))
+        });
+        let cassette = match (&config.replay, &config.record) {
+            (Some(path), _) => Some(Arc::new(Cassette::replay(path)?)),
+            (None, Some(path)) => Some(Arc::new(Cassette::record(path)?)),
+            (None, None) => None,
+        };
         let llm = LlmClient::new(
             &config.api_base_url,
             &config.api_key,
             &config.model,
             config.provider,
-        );
+        )?
+        .with_retry(config.retry.clone())
+        .with_limiter(limiter.clone())
+        .with_price(config.pricing.lookup(&config.model))
+        .with_seed(request_seed)
+        .with_cassette(cassette.clone())
+        .with_cache(cache.clone());
         let judge_llm = LlmClient::new(
             &config.judge_api_base_url,
             &config.judge_api_key,
             &config.judge_model,
             config.judge_provider,
-        );
+        )?
+        .with_retry(config.retry.clone())
+        .with_limiter(limiter)
+        .with_price(config.pricing.lookup(&config.judge_model))
+        .with_seed(request_seed)
+        .with_cassette(cassette)
+        .with_cache(cache);
+        Ok(Self::with_clients(config, llm, judge_llm))
+    }
+
+    /// Builds an arena around pre-constructed clients, e.g. custom [`crate::llm::LlmBackend`]s.
+    /// An unset [`Config::seed`] is filled in with a random one.
+    pub fn with_clients(mut config: Config, llm: LlmClient, judge_llm: LlmClient) -> Self {
+        config.seed.get_or_insert_with(rand::random);
         let orchestrator = Orchestrator::new(&config);
         Self {
             config,
@@ -120,39 +400,113 @@ impl Arena {
     }
 
     pub async fn run(&self, problem: &str) -> Result<EvolutionResult> {
+        self.run_from(problem, self.initial_state(), Usage::default())
+            .await
+    }
+
+    /// Like [`run`](Self::run) but continues from a checkpointed state.
+    pub async fn run_from(
+        &self,
+        problem: &str,
+        state: RunState,
+        prior: Usage,
+    ) -> Result<EvolutionResult> {
         self.print_header(problem);
+        if state.next_gen > 0 {
+            println!(
+                "  {} generation {} of {}",
+                "Resuming after".bold().cyan(),
+                state.next_gen,
+                self.config.max_generations,
+            );
+            println!();
+        }
 
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
-        let config = self.config.clone();
-        let problem_owned = problem.to_string();
+        let control = RunControl::default();
+        let run = self.resume_with_progress(problem, state, prior, tx, &control);
+        let print = async {
+            while let Some(event) = rx.recv().await {
+                Self::print_event(&event);
+            }
+        };
 
-        let handle = tokio::spawn(async move {
-            let arena = Arena::new(config);
-            arena.run_with_progress(&problem_owned, tx).await
-        });
+        let (result, ()) = tokio::join!(run, print);
+        result
+    }
 
-        while let Some(event) = rx.recv().await {
-            Self::print_event(&event);
+    /// A fresh generation-zero state with a random initial population.
+    pub fn initial_state(&self) -> RunState {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed());
+        RunState {
+            next_gen: 0,
+            population: evolution::create_initial_population(&self.config, &mut rng),
+            elite_cache: Vec::new(),
+            knowledge: KnowledgeBase::new(15),
+            conclusion_history: ConclusionHistory::new(),
+            best_ever: None,
+            generation_scores: Vec::new(),
+            pareto_front: Vec::new(),
+            archive: EliteArchive::default(),
+            converged: false,
+            rng,
         }
-
-        handle.await?
     }
 
+    /// Runs from generation zero, reporting progress on `tx`. `control` can
+    /// pause or stop the run from another task.
     pub async fn run_with_progress(
         &self,
         problem: &str,
         tx: UnboundedSender<ArenaEvent>,
+        control: &RunControl,
+    ) -> Result<EvolutionResult> {
+        self.resume_with_progress(problem, self.initial_state(), Usage::default(), tx, control)
+            .await
+    }
+
+    /// Continues the evolution loop from `state`. `prior` is the usage
+    /// recorded before `state` was saved, so budgets span the whole run.
+    pub async fn resume_with_progress(
+        &self,
+        problem: &str,
+        mut state: RunState,
+        prior: Usage,
+        tx: UnboundedSender<ArenaEvent>,
+        control: &RunControl,
     ) -> Result<EvolutionResult> {
-        let mut rng = StdRng::from_entropy();
-        let mut population = evolution::create_initial_population(&self.config, &mut rng);
-        let mut knowledge = KnowledgeBase::new(15);
-        let mut conclusion_history = ConclusionHistory::new();
-        let mut best_ever: Option<(Team, TeamOutput, FitnessScore)> = None;
+        let mut clock = RunClock {
+            started: Instant::now(),
+            prior,
+        };
+        let mut stop_reason = if state.converged {
+            StopReason::Converged
+        } else {
+            StopReason::MaxGenerations
+        };
+        let first_gen = if state.converged {
+            self.config.max_generations
+        } else {
+            state.next_gen
+        };
 
-        let mut elite_cache: Vec<(Team, TeamOutput, FitnessScore)> = Vec::new();
-        let mut cumulative_tokens: u64 = 0;
+        for gen in first_gen..self.config.max_generations {
+            if self
+                .hold_or_stop(control, state.best_ever.is_some(), gen, &mut clock, &tx)
+                .await
+            {
+                stop_reason = StopReason::Stopped;
+                break;
+            }
+            // Like a stop, budgets wait until there is a team to synthesise.
+            if let Some(budget) = self
+                .exhausted_budget(&clock)
+                .filter(|_| state.best_ever.is_some())
+            {
+                stop_reason = self.report_budget(budget, gen, &clock, &tx);
+                break;
+            }
 
-        for gen in 0..self.config.max_generations {
             let _ = tx.send(ArenaEvent::GenerationStarted {
                 gen: gen + 1,
                 total: self.config.max_generations,
@@ -160,7 +514,7 @@ impl Arena {
 
             let mut scored: Vec<ScoredTeam> = Vec::new();
 
-            for (team, output, score) in elite_cache.drain(..) {
+            for (team, output, score) in state.elite_cache.drain(..) {
                 scored.push(ScoredTeam {
                     team,
                     output,
@@ -168,7 +522,8 @@ impl Arena {
                 });
             }
 
-            let new_teams: Vec<Team> = population
+            let new_teams: Vec<Team> = state
+                .population
                 .iter()
                 .filter(|t| !scored.iter().any(|s| s.team.id == t.id))
                 .cloned()
@@ -176,29 +531,63 @@ impl Arena {
 
             if !new_teams.is_empty() {
                 let _ = tx.send(ArenaEvent::PhaseChanged(Phase::AgentsWorking));
-                let team_outputs = self.execute_population(&new_teams, problem).await;
+                // Until a team has been scored, neither a stop nor a budget
+                // cuts the agents short, so there is always a team to
+                // synthesise.
+                let stoppable = state.best_ever.is_some();
+                let agents = self.execute_population(&new_teams, problem, &tx);
+                let team_outputs = if stoppable {
+                    self.interruptible(&clock, control, agents).await
+                } else {
+                    Ok(agents.await)
+                };
+                self.report_usage(&clock, &tx);
+                let team_outputs = match team_outputs {
+                    Ok(outputs) => outputs,
+                    Err(interrupt) => {
+                        stop_reason = self.report_interrupt(interrupt, gen, &clock, &tx);
+                        break;
+                    }
+                };
 
                 for (_idx, result) in &team_outputs {
                     if let Ok(output) = result {
-                        cumulative_tokens += output.total_tokens as u64;
                         for w in &output.warnings {
                             let _ = tx.send(ArenaEvent::Warning(w.clone()));
                         }
                     }
                 }
-                let _ = tx.send(ArenaEvent::TokenUpdate { total_tokens: cumulative_tokens });
+
+                if self
+                    .hold_or_stop(control, stoppable, gen, &mut clock, &tx)
+                    .await
+                {
+                    stop_reason = StopReason::Stopped;
+                    break;
+                }
 
                 let _ = tx.send(ArenaEvent::PhaseChanged(Phase::Judging));
-                let mut new_scored = self
-                    .evaluate_population(
-                        &new_teams,
-                        &team_outputs,
-                        problem,
-                        gen,
-                        &conclusion_history,
-                    )
-                    .await;
-                scored.append(&mut new_scored);
+                let judging = self.evaluate_population(
+                    &new_teams,
+                    &team_outputs,
+                    problem,
+                    gen,
+                    &state.conclusion_history,
+                );
+                // The first verdicts always complete so there is a team to synthesise.
+                let new_scored = if state.best_ever.is_some() {
+                    self.interruptible(&clock, control, judging).await
+                } else {
+                    Ok(judging.await)
+                };
+                self.report_usage(&clock, &tx);
+                match new_scored {
+                    Ok(mut new_scored) => scored.append(&mut new_scored),
+                    Err(interrupt) => {
+                        stop_reason = self.report_interrupt(interrupt, gen, &clock, &tx);
+                        break;
+                    }
+                }
             }
 
             let _ = tx.send(ArenaEvent::PhaseChanged(Phase::Scoring));
@@ -225,6 +614,7 @@ impl Arena {
                     consistency: s.score.consistency,
                     efficiency: s.score.efficiency,
                     diversity_penalty: s.score.diversity_penalty,
+                    judge_critique: s.score.judge_critique.clone(),
                 })
                 .collect();
 
@@ -241,23 +631,40 @@ impl Arena {
                 }).collect(),
             ));
 
+            let teams: Vec<&Team> = scored.iter().map(|s| &s.team).collect();
+            let species = species::species_sizes(&species::speciate(
+                &teams,
+                self.config.species_threshold,
+            ));
+
+            state.generation_scores.push(scores.clone());
             let _ = tx.send(ArenaEvent::GenerationComplete {
                 gen: gen + 1,
                 scores,
                 best_name: gen_best_name.clone(),
                 best_score: gen_best_score,
+                species,
             });
+            if self.config.islands > 1 {
+                let _ = tx.send(ArenaEvent::IslandScores {
+                    gen: gen + 1,
+                    islands: self.island_scores(&scored),
+                });
+            }
 
             for st in &scored {
-                conclusion_history.record(&st.output, st.score.total);
+                state.conclusion_history.record(&st.output, st.score.total);
             }
+            evolution::update_pareto_front(&mut state.pareto_front, &scored);
+            state.archive.insert(&scored);
 
-            if best_ever
+            if state
+                .best_ever
                 .as_ref()
-                .map_or(true, |(_, _, s)| gen_best_score > s.total)
+                .is_none_or(|(_, _, s)| gen_best_score > s.total)
             {
                 let top = scored.remove(0);
-                best_ever = Some((top.team.clone(), top.output.clone(), top.score.clone()));
+                state.best_ever = Some((top.team.clone(), top.output.clone(), top.score.clone()));
                 scored.insert(
                     0,
                     ScoredTeam {
@@ -273,47 +680,71 @@ impl Arena {
                     gen: gen + 1,
                     score: gen_best_score,
                 });
+                stop_reason = StopReason::Converged;
+                state.converged = true;
+                state.next_gen = gen + 1;
+                self.save_checkpoint(problem, &state, &clock, &tx);
                 break;
             }
 
             for st in &scored {
-                knowledge.extract_from_critique(&st.score.judge_critique);
+                state.knowledge.extract_from_critique(&st.score.judge_critique);
             }
             let conflict_pairs: Vec<(String, &TeamOutput)> = scored
                 .iter()
                 .map(|st| (st.team.name.clone(), &st.output))
                 .collect();
-            knowledge.extract_conflicts(&conflict_pairs);
+            state.knowledge.extract_conflicts(&conflict_pairs);
 
             if gen + 1 < self.config.max_generations {
+                if self.hold_or_stop(control, true, gen, &mut clock, &tx).await {
+                    stop_reason = StopReason::Stopped;
+                    break;
+                }
                 let _ = tx.send(ArenaEvent::PhaseChanged(Phase::Evolving));
+                let kept: usize = (0..self.config.islands)
+                    .map(|i| evolution::island_config(&self.config, i).elite_count)
+                    .sum();
                 let _ = tx.send(ArenaEvent::Evolving {
-                    kept: self.config.elite_count,
-                    spawning: self.config.population_size - self.config.elite_count,
+                    kept,
+                    spawning: self.config.population_size - kept,
                 });
-                let members = evolution::next_generation(
+                let (members, migrations) = evolution::next_generation_islands(
                     &mut scored,
                     &self.config,
                     gen + 1,
-                    &knowledge,
-                    &mut rng,
+                    &state.knowledge,
+                    &state.archive,
+                    &mut state.rng,
                 );
-                population = Vec::with_capacity(members.len());
+                if !migrations.is_empty() {
+                    let _ = tx.send(ArenaEvent::Migrated {
+                        gen: gen + 1,
+                        migrations,
+                    });
+                }
+                state.population = Vec::with_capacity(members.len());
                 for m in members {
-                    population.push(m.team.clone());
+                    state.population.push(m.team.clone());
                     if let Some((output, score)) = m.cached {
-                        elite_cache.push((m.team, output, score));
+                        state.elite_cache.push((m.team, output, score));
                     }
                 }
             }
+
+            state.next_gen = gen + 1;
+            self.save_checkpoint(problem, &state, &clock, &tx);
         }
 
-        let (best_team, best_output, best_score) =
-            best_ever.expect("at least one generation must run");
+        let (best_team, best_output, best_score) = state
+            .best_ever
+            .ok_or_else(|| anyhow!("Evolution stopped before any team was scored ({stop_reason})"))?;
 
         let _ = tx.send(ArenaEvent::PhaseChanged(Phase::Synthesising));
         let _ = tx.send(ArenaEvent::SynthesisStarted);
-        let synthesis = self.synthesise(problem, &best_output).await?;
+        let synthesis = self.synthesise(problem, &best_output).await;
+        self.report_usage(&clock, &tx);
+        let synthesis = synthesis?;
         let generations_run = best_team.generation + 1;
 
         Ok(EvolutionResult {
@@ -322,22 +753,280 @@ impl Arena {
             best_score,
             synthesis,
             generations_run,
+            total_cost: self.usage(&clock).cost,
+            stop_reason,
+            seed: self.seed(),
+            generation_scores: state.generation_scores,
+            total_tokens: self.usage(&clock).tokens,
+            pareto_front: state.pareto_front,
+            archive: state.archive,
         })
     }
 
+    /// Best and mean score of every island; `scored` is sorted best first.
+    fn island_scores(&self, scored: &[ScoredTeam]) -> Vec<IslandScore> {
+        (0..self.config.islands)
+            .map(|island| {
+                let members: Vec<&ScoredTeam> =
+                    scored.iter().filter(|s| s.team.island == island).collect();
+                let total: f64 = members.iter().map(|s| s.score.total).sum();
+                IslandScore {
+                    island,
+                    teams: members.len(),
+                    best_name: members
+                        .first()
+                        .map(|s| s.team.name.clone())
+                        .unwrap_or_default(),
+                    best_score: members.first().map(|s| s.score.total).unwrap_or(0.0),
+                    mean_score: total / members.len().max(1) as f64,
+                    mutation_rate: evolution::island_config(&self.config, island).mutation_rate,
+                }
+            })
+            .collect()
+    }
+
+    /// Tokens used so far by the agent and judge clients together.
+    pub fn total_tokens(&self) -> u64 {
+        self.llm.tokens_used() + self.judge_llm.tokens_used()
+    }
+
+    fn has_budget(&self) -> bool {
+        self.config.max_total_tokens.is_some()
+            || self.config.max_duration.is_some()
+            || self.config.max_cost.is_some()
+    }
+
+    /// The seed driving this run's random choices.
+    pub fn seed(&self) -> u64 {
+        self.config.seed.unwrap_or_default()
+    }
+
+    fn usage(&self, clock: &RunClock) -> Usage {
+        Usage {
+            tokens: clock.prior.tokens + self.total_tokens(),
+            cost: clock.prior.cost + self.total_cost(),
+            elapsed: clock.prior.elapsed + clock.started.elapsed(),
+        }
+    }
+
+    fn exhausted_budget(&self, clock: &RunClock) -> Option<Budget> {
+        let usage = self.usage(clock);
+        if self
+            .config
+            .max_total_tokens
+            .is_some_and(|limit| usage.tokens >= limit)
+        {
+            Some(Budget::Tokens)
+        } else if self
+            .config
+            .max_duration
+            .is_some_and(|limit| usage.elapsed >= limit)
+        {
+            Some(Budget::Duration)
+        } else if self
+            .config
+            .max_cost
+            .is_some_and(|limit| usage.cost >= limit)
+        {
+            Some(Budget::Cost)
+        } else {
+            None
+        }
+    }
+
+    /// Runs `phase` until it finishes, a budget runs out or `control` is
+    /// stopped. Dropping the phase future cancels its in-flight LLM requests.
+    async fn interruptible<T>(
+        &self,
+        clock: &RunClock,
+        control: &RunControl,
+        phase: impl std::future::Future<Output = T>,
+    ) -> std::result::Result<T, Interrupt> {
+        let watchdog = async {
+            if !self.has_budget() {
+                return std::future::pending().await;
+            }
+            loop {
+                if let Some(budget) = self.exhausted_budget(clock) {
+                    return budget;
+                }
+                tokio::time::sleep(BUDGET_POLL_INTERVAL).await;
+            }
+        };
+        let stopped = control.wait_until(|s| *s == ControlState::Stopped);
+        tokio::select! {
+            output = phase => Ok(output),
+            budget = watchdog => Err(Interrupt::Budget(budget)),
+            () = stopped => Err(Interrupt::Stopped),
+        }
+    }
+
+    /// Waits out a pause at a phase boundary; paused time does not count
+    /// towards `--max-duration`. `true` if the run should stop now, which
+    /// `can_stop` defers until a team has been scored.
+    async fn hold_or_stop(
+        &self,
+        control: &RunControl,
+        can_stop: bool,
+        gen: usize,
+        clock: &mut RunClock,
+        tx: &UnboundedSender<ArenaEvent>,
+    ) -> bool {
+        if control.is_paused() {
+            let _ = tx.send(ArenaEvent::Paused { gen: gen + 1 });
+            let paused_at = Instant::now();
+            control.wait_until(|s| *s != ControlState::Paused).await;
+            clock.started += paused_at.elapsed();
+            if !control.is_stopped() {
+                let _ = tx.send(ArenaEvent::Resumed);
+            }
+        }
+        if can_stop && control.is_stopped() {
+            let _ = tx.send(ArenaEvent::Stopped { gen: gen + 1 });
+            return true;
+        }
+        false
+    }
+
+    fn report_interrupt(
+        &self,
+        interrupt: Interrupt,
+        gen: usize,
+        clock: &RunClock,
+        tx: &UnboundedSender<ArenaEvent>,
+    ) -> StopReason {
+        match interrupt {
+            Interrupt::Budget(budget) => self.report_budget(budget, gen, clock, tx),
+            Interrupt::Stopped => {
+                let _ = tx.send(ArenaEvent::Stopped { gen: gen + 1 });
+                StopReason::Stopped
+            }
+        }
+    }
+
+    fn report_budget(
+        &self,
+        budget: Budget,
+        gen: usize,
+        clock: &RunClock,
+        tx: &UnboundedSender<ArenaEvent>,
+    ) -> StopReason {
+        let usage = self.usage(clock);
+        let detail = match budget {
+            Budget::Tokens => format!(
+                "{} of {} tokens used",
+                usage.tokens,
+                self.config.max_total_tokens.unwrap_or_default(),
+            ),
+            Budget::Duration => format!(
+                "{:.0}s of {}s elapsed",
+                usage.elapsed.as_secs_f64(),
+                self.config.max_duration.unwrap_or_default().as_secs(),
+            ),
+            Budget::Cost => format!(
+                "${:.4} of ${:.2} spent",
+                usage.cost,
+                self.config.max_cost.unwrap_or_default(),
+            ),
+        };
+        let _ = tx.send(ArenaEvent::BudgetExhausted {
+            gen: gen + 1,
+            budget,
+            detail,
+        });
+        StopReason::BudgetExhausted(budget)
+    }
+
+    fn report_usage(&self, clock: &RunClock, tx: &UnboundedSender<ArenaEvent>) {
+        let usage = self.usage(clock);
+        let (cache_hits, cache_misses) = self.cache_stats();
+        let _ = tx.send(ArenaEvent::TokenUpdate {
+            total_tokens: usage.tokens,
+            total_cost: usage.cost,
+            cache_hits,
+            cache_misses,
+        });
+        self.report_retries(tx);
+    }
+
+    /// Response-cache `(hits, misses)` across both clients, counting a cache
+    /// they share only once.
+    fn cache_stats(&self) -> (u64, u64) {
+        match (self.llm.cache(), self.judge_llm.cache()) {
+            (Some(a), Some(b)) if Arc::ptr_eq(a, b) => a.stats(),
+            (a, b) => {
+                let (ah, am) = a.map(|c| c.stats()).unwrap_or_default();
+                let (bh, bm) = b.map(|c| c.stats()).unwrap_or_default();
+                (ah + bh, am + bm)
+            }
+        }
+    }
+
+    fn save_checkpoint(
+        &self,
+        problem: &str,
+        state: &RunState,
+        clock: &RunClock,
+        tx: &UnboundedSender<ArenaEvent>,
+    ) {
+        let Some(path) = &self.config.checkpoint_path else {
+            return;
+        };
+        match Checkpoint::save(path, problem, &self.config, state, self.usage(clock)) {
+            Ok(()) => {
+                let _ = tx.send(ArenaEvent::CheckpointSaved {
+                    gen: state.next_gen,
+                    path: path.clone(),
+                });
+            }
+            Err(e) => {
+                let _ = tx.send(ArenaEvent::Warning(format!("Checkpoint not saved: {e:#}")));
+            }
+        }
+    }
+
+    /// USD spent so far by the agent and judge clients together.
+    pub fn total_cost(&self) -> f64 {
+        self.llm.spent() + self.judge_llm.spent()
+    }
+
+    fn report_retries(&self, tx: &UnboundedSender<ArenaEvent>) {
+        for (role, client) in [("Agent", &self.llm), ("Judge", &self.judge_llm)] {
+            if let Some(summary) = client.take_retry_summary() {
+                let _ = tx.send(ArenaEvent::Warning(format!("{role} LLM requests: {summary}")));
+            }
+        }
+    }
+
     async fn execute_population(
         &self,
         population: &[Team],
         problem: &str,
+        tx: &UnboundedSender<ArenaEvent>,
     ) -> Vec<(usize, Result<TeamOutput>)> {
-        let concurrency = population.len();
+        let concurrency = self
+            .config
+            .max_concurrency
+            .unwrap_or(population.len())
+            .clamp(1, population.len().max(1));
 
         let items: Vec<(usize, Team)> =
             population.iter().cloned().enumerate().collect();
 
         let results: Vec<(usize, Result<TeamOutput>)> = stream::iter(items)
             .map(|(idx, team)| async move {
-                let output = team.execute(problem, &self.llm, &self.config).await;
+                let forward = |agent: &Agent, delta: &str| {
+                    let _ = tx.send(ArenaEvent::AgentChunk {
+                        team: team.name.clone(),
+                        agent_id: agent.id,
+                        agent: agent.genotype.name.clone(),
+                        delta: delta.to_string(),
+                    });
+                };
+                let on_delta: Option<&AgentSink<'_>> = self.config.stream.then_some(&forward);
+                let output = team
+                    .execute(problem, &self.llm, &self.config, on_delta)
+                    .await;
                 (idx, output)
             })
             .buffer_unordered(concurrency)
@@ -390,6 +1079,7 @@ impl Arena {
                                 diversity_penalty: 0.0,
                                 total: 1.0,
                                 judge_critique: String::new(),
+                                critiques: Vec::new(),
                             });
                         ScoredTeam {
                             team,
@@ -451,10 +1141,11 @@ impl Arena {
         println!("{}", "=".repeat(56).dimmed());
         println!();
 
-        let truncated: String = if problem.len() > 120 {
-            format!("{}...", &problem[..117])
+        let first_line = problem.lines().next().unwrap_or_default();
+        let truncated: String = if first_line.chars().count() > 120 || problem.lines().nth(1).is_some() {
+            format!("{}...", first_line.chars().take(117).collect::<String>())
         } else {
-            problem.to_string()
+            first_line.to_string()
         };
         println!("  {} {}", "Problem:".bold(), truncated.white());
         println!();
@@ -495,6 +1186,60 @@ impl Arena {
             self.config.api_base_url,
         );
 
+        let mut limits = Vec::new();
+        if let Some(n) = self.config.max_concurrency {
+            limits.push(format!("{n} concurrent"));
+        }
+        if let Some(n) = self.config.rpm {
+            limits.push(format!("{n} req/min"));
+        }
+        if let Some(n) = self.config.tpm {
+            limits.push(format!("{n} tok/min"));
+        }
+        if !limits.is_empty() {
+            println!("  {} {}", "Rate limit:".bold(), limits.join(", "));
+        }
+        match self.llm.price() {
+            Some(price) => println!(
+                "  {} ${:.2} in / ${:.2} out per 1M tokens",
+                "Pricing:".bold(),
+                price.input_per_mtok,
+                price.output_per_mtok,
+            ),
+            None => println!(
+                "  {} {}",
+                "Pricing:".bold(),
+                "unknown for this model (cost reported as $0; see --price)".dimmed(),
+            ),
+        }
+        println!("  {} {}", "Seed:".bold(), self.seed());
+        if let Some(cache) = self.llm.cache() {
+            let scope = if self.config.cache_deterministic_only {
+                " (temperature 0 only)"
+            } else {
+                ""
+            };
+            println!("  {} {}{}", "Cache:".bold(), cache.dir().display(), scope);
+        }
+        if let Some(path) = &self.config.replay {
+            println!("  {} replaying {}", "Cassette:".bold(), path.display());
+        } else if let Some(path) = &self.config.record {
+            println!("  {} recording to {}", "Cassette:".bold(), path.display());
+        }
+        let mut budgets = Vec::new();
+        if let Some(limit) = self.config.max_cost {
+            budgets.push(format!("${limit:.2}"));
+        }
+        if let Some(limit) = self.config.max_total_tokens {
+            budgets.push(format!("{limit} tokens"));
+        }
+        if let Some(limit) = self.config.max_duration {
+            budgets.push(format!("{}s", limit.as_secs()));
+        }
+        if !budgets.is_empty() {
+            println!("  {} {}", "Budget:".bold(), budgets.join(", "));
+        }
+
         if self.config.judge_model != self.config.model
             || self.config.judge_provider != self.config.provider
         {
@@ -530,6 +1275,7 @@ impl Arena {
                 scores,
                 best_name,
                 best_score,
+                species,
                 ..
             } => {
                 for (i, ts) in scores.iter().enumerate() {
@@ -562,6 +1308,10 @@ impl Arena {
                     best_name.green().bold(),
                     format!("{:.2}", best_score).green(),
                 );
+                println!(
+                    "   {}",
+                    format!("Species: {}", species::summary(species)).dimmed()
+                );
             }
             ArenaEvent::Converged { gen, score } => {
                 println!(
@@ -574,6 +1324,55 @@ impl Arena {
                     .bold()
                 );
             }
+            ArenaEvent::BudgetExhausted { gen, budget, detail } => {
+                println!(
+                    "\n{}",
+                    format!(
+                        "Stopping in generation {}: {} budget exhausted ({})",
+                        gen, budget, detail
+                    )
+                    .yellow()
+                    .bold()
+                );
+            }
+            ArenaEvent::Paused { gen } => {
+                println!("\n{}", format!("Paused before generation {gen} continues").yellow());
+            }
+            ArenaEvent::Resumed => {
+                println!("{}", "Resumed".yellow());
+            }
+            ArenaEvent::Stopped { gen } => {
+                println!(
+                    "\n{}",
+                    format!("Stopped in generation {gen}; synthesising the best team so far")
+                        .yellow()
+                        .bold()
+                );
+            }
+            ArenaEvent::IslandScores { islands, .. } => {
+                for island in islands {
+                    println!(
+                        "   {} #{}: best {} {:.2}, mean {:.2} over {} teams",
+                        "Island".blue().bold(),
+                        island.island + 1,
+                        island.best_name,
+                        island.best_score,
+                        island.mean_score,
+                        island.teams,
+                    );
+                }
+            }
+            ArenaEvent::Migrated { migrations, .. } => {
+                for m in migrations {
+                    println!(
+                        "   {} {} from island #{} to #{}",
+                        "Migrating:".blue().bold(),
+                        m.team,
+                        m.from + 1,
+                        m.to + 1,
+                    );
+                }
+            }
             ArenaEvent::Evolving { kept, spawning } => {
                 println!(
                     "   {} keeping top {}, spawning {} mutants...",
@@ -591,9 +1390,34 @@ impl Arena {
                         .cyan()
                 );
             }
+            ArenaEvent::CheckpointSaved { path, .. } => {
+                println!(
+                    "   {}",
+                    format!("Checkpoint saved to {}", path.display()).dimmed()
+                );
+            }
             ArenaEvent::Warning(msg) => {
                 println!("   {}: {}", "Warning".yellow(), msg);
             }
+            ArenaEvent::TokenUpdate {
+                total_tokens,
+                total_cost,
+                cache_hits,
+                cache_misses,
+            } => {
+                let cache = if cache_hits + cache_misses > 0 {
+                    format!(" (cache: {cache_hits} hits, {cache_misses} misses)")
+                } else {
+                    String::new()
+                };
+                println!(
+                    "   {} {} tokens, ${:.4}{}",
+                    "Spent:".dimmed(),
+                    total_tokens,
+                    total_cost,
+                    cache.dimmed(),
+                );
+            }
             _ => {}
         }
     }
@@ -614,3 +1438,404 @@ fn score_bar(score: f64) -> String {
         bar.red().to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::Niche;
+    use crate::config::Provider;
+
+    fn mock_config() -> Config {
+        Config {
+            provider: Provider::Mock,
+            model: "mock".into(),
+            judge_provider: Provider::Mock,
+            judge_model: "mock".into(),
+            population_size: 4,
+            team_size: 3,
+            max_generations: 3,
+            fitness_threshold: 10.0,
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn unseeded_reruns_share_cache_entries() {
+        let dir = std::env::temp_dir().join(format!("emas-rerun-{}", Uuid::new_v4()));
+        // Nine regular agents per team: every strategy template answers in
+        // every team, so generation 0 makes the same agent calls each run.
+        let config = Config {
+            population_size: 2,
+            team_size: 10,
+            max_generations: 1,
+            cache_dir: Some(dir.clone()),
+            ..mock_config()
+        };
+        let mut hits = Vec::new();
+        for _ in 0..2 {
+            let arena = Arena::new(config.clone()).unwrap();
+            let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+            arena
+                .run_with_progress("Is 7919 prime?", tx, &RunControl::default())
+                .await
+                .unwrap();
+            hits.push(arena.llm.cache().unwrap().stats().0);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // The second run finds every regular agent's reply on disk.
+        assert!(config.seed.is_none());
+        assert!(hits[1] >= 18, "cache hits per run: {hits:?}");
+    }
+
+    #[tokio::test]
+    async fn mock_run_completes_every_generation() {
+        let arena = Arena::new(mock_config()).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = arena
+            .run_with_progress("Is 7919 prime?", tx, &RunControl::default())
+            .await
+            .unwrap();
+
+        let mut completed = 0;
+        while let Ok(event) = rx.try_recv() {
+            if let ArenaEvent::GenerationComplete { scores, .. } = event {
+                assert_eq!(scores.len(), 4);
+                completed += 1;
+            }
+        }
+        assert_eq!(completed, 3);
+        assert!(result.best_score.total > 1.0);
+        assert!(result.best_score.judge_critique.contains("Mock verdict"));
+        assert!(result.synthesis.contains("Is 7919 prime?"));
+        assert_eq!(result.best_output.agent_outputs.len(), 3);
+        assert_eq!(result.stop_reason, StopReason::MaxGenerations);
+    }
+
+    #[tokio::test]
+    async fn streamed_chunks_rebuild_agent_answers() {
+        let config = Config {
+            max_generations: 1,
+            stream: true,
+            ..mock_config()
+        };
+        let arena = Arena::new(config).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = arena.run_with_progress("Is 7919 prime?", tx, &RunControl::default()).await.unwrap();
+
+        let mut streamed: std::collections::HashMap<(String, Uuid), String> =
+            std::collections::HashMap::new();
+        while let Ok(event) = rx.try_recv() {
+            if let ArenaEvent::AgentChunk {
+                team,
+                agent_id,
+                delta,
+                ..
+            } = event
+            {
+                streamed.entry((team, agent_id)).or_default().push_str(&delta);
+            }
+        }
+        for output in &result.best_output.agent_outputs {
+            let key = (result.best_team.name.clone(), output.agent_id);
+            assert_eq!(streamed.get(&key), Some(&output.content));
+        }
+    }
+
+    #[tokio::test]
+    async fn cost_budget_stops_after_first_generation() {
+        let config = Config {
+            pricing: crate::llm::PricingTable::default()
+                .with_override("mock", crate::llm::ModelPrice::new(1000.0, 1000.0)),
+            max_cost: Some(0.01),
+            ..mock_config()
+        };
+        let arena = Arena::new(config).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = arena.run_with_progress("Is 7919 prime?", tx, &RunControl::default()).await.unwrap();
+
+        let mut started = 0;
+        while let Ok(event) = rx.try_recv() {
+            if let ArenaEvent::GenerationStarted { .. } = event {
+                started += 1;
+            }
+        }
+        assert_eq!(started, 1);
+        assert!(result.total_cost > 0.01);
+        assert_eq!(result.stop_reason, StopReason::BudgetExhausted(Budget::Cost));
+        assert!(!result.synthesis.is_empty());
+    }
+
+    /// Mock agents that answer one after another, `step` apart.
+    struct SlowBackend {
+        inner: crate::llm::MockBackend,
+        step: Duration,
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::llm::LlmBackend for SlowBackend {
+        fn name(&self) -> &str {
+            "slow-mock"
+        }
+
+        fn model(&self) -> &str {
+            self.inner.model()
+        }
+
+        fn capabilities(&self) -> crate::llm::BackendCapabilities {
+            self.inner.capabilities()
+        }
+
+        async fn chat(
+            &self,
+            request: &crate::llm::ChatRequest<'_>,
+        ) -> Result<crate::llm::LlmResponse> {
+            let n = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            tokio::time::sleep(self.step * n).await;
+            self.inner.chat(request).await
+        }
+    }
+
+    fn slow_arena(config: Config, step: Duration, price: Option<crate::llm::ModelPrice>) -> Arena {
+        let llm = LlmClient::from_backend(Arc::new(SlowBackend {
+            inner: crate::llm::MockBackend::new("mock"),
+            step,
+            calls: Default::default(),
+        }))
+        .with_price(price);
+        let judge = LlmClient::from_backend(Arc::new(crate::llm::MockBackend::new("mock")));
+        Arena::with_clients(config, llm, judge)
+    }
+
+    #[tokio::test]
+    async fn duration_budget_waits_for_first_generation_verdicts() {
+        let config = Config {
+            max_duration: Some(Duration::from_millis(20)),
+            ..mock_config()
+        };
+        let arena = slow_arena(config, Duration::from_millis(30), None);
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = arena
+            .run_with_progress("Is 7919 prime?", tx, &RunControl::default())
+            .await
+            .unwrap();
+
+        assert_eq!(result.stop_reason, StopReason::BudgetExhausted(Budget::Duration));
+        assert_eq!(result.generation_scores.len(), 1);
+        assert!(!result.synthesis.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cost_budget_spent_by_first_agents_still_yields_a_result() {
+        let config = Config {
+            max_cost: Some(0.01),
+            ..mock_config()
+        };
+        let price = Some(crate::llm::ModelPrice::new(1000.0, 1000.0));
+        let arena = slow_arena(config, Duration::from_millis(25), price);
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = arena
+            .run_with_progress("Is 7919 prime?", tx, &RunControl::default())
+            .await
+            .unwrap();
+
+        assert_eq!(result.stop_reason, StopReason::BudgetExhausted(Budget::Cost));
+        assert_eq!(result.generation_scores.len(), 1);
+        assert_eq!(result.generation_scores[0].len(), 4);
+    }
+
+    #[tokio::test]
+    async fn token_budget_reports_exhaustion_and_keeps_best_so_far() {
+        let config = Config {
+            max_total_tokens: Some(1),
+            ..mock_config()
+        };
+        let arena = Arena::new(config).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = arena.run_with_progress("Is 7919 prime?", tx, &RunControl::default()).await.unwrap();
+
+        let mut exhausted = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let ArenaEvent::BudgetExhausted { gen, budget, .. } = event {
+                exhausted.push((gen, budget));
+            }
+        }
+        assert_eq!(exhausted, vec![(2, Budget::Tokens)]);
+        assert_eq!(result.stop_reason, StopReason::BudgetExhausted(Budget::Tokens));
+        assert!(result.synthesis.contains("Is 7919 prime?"));
+    }
+
+    #[tokio::test]
+    async fn stop_while_paused_still_synthesises_first_generation() {
+        let arena = Arena::new(mock_config()).unwrap();
+        let control = RunControl::new();
+        control.pause();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let run = arena.run_with_progress("Is 7919 prime?", tx, &control);
+        let watch = async {
+            let mut seen = Vec::new();
+            while let Some(event) = rx.recv().await {
+                match event {
+                    ArenaEvent::Paused { gen } => {
+                        control.stop();
+                        seen.push(format!("paused {gen}"));
+                    }
+                    ArenaEvent::Resumed => seen.push("resumed".into()),
+                    ArenaEvent::Stopped { gen } => seen.push(format!("stopped {gen}")),
+                    _ => {}
+                }
+            }
+            seen
+        };
+        let (result, seen) = tokio::join!(run, watch);
+        let result = result.unwrap();
+
+        // No team is scored before the first verdicts, so the stop waits for them.
+        assert_eq!(seen, vec!["paused 1", "stopped 1"]);
+        assert_eq!(result.stop_reason, StopReason::Stopped);
+        assert_eq!(result.generation_scores.len(), 1);
+        assert!(result.synthesis.contains("Is 7919 prime?"));
+    }
+
+    #[tokio::test]
+    async fn islands_report_scores_and_exchange_their_best_teams() {
+        let config = Config {
+            population_size: 6,
+            islands: 2,
+            migration_interval: 1,
+            island_mutation_rates: vec![0.1, 0.9],
+            ..mock_config()
+        };
+        let arena = Arena::new(config).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        arena
+            .run_with_progress("Is 7919 prime?", tx, &RunControl::default())
+            .await
+            .unwrap();
+
+        let (mut island_reports, mut migrations) = (Vec::new(), Vec::new());
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                ArenaEvent::IslandScores { islands, .. } => island_reports.push(islands),
+                ArenaEvent::Migrated { migrations: moved, .. } => migrations.push(moved),
+                _ => {}
+            }
+        }
+        assert_eq!(island_reports.len(), 3);
+        for islands in &island_reports {
+            assert_eq!(islands.iter().map(|i| i.teams).collect::<Vec<_>>(), vec![3, 3]);
+            assert_eq!(islands[1].mutation_rate, 0.9);
+        }
+        // One migrant each way before generations 2 and 3.
+        assert_eq!(migrations.len(), 2);
+        let routes: Vec<(usize, usize)> = migrations[0].iter().map(|m| (m.from, m.to)).collect();
+        assert_eq!(routes, vec![(0, 1), (1, 0)]);
+    }
+
+    #[tokio::test]
+    async fn archive_keeps_the_best_team_of_each_niche() {
+        let config = Config {
+            archive_parent_rate: 0.5,
+            ..mock_config()
+        };
+        let arena = Arena::new(config).unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = arena
+            .run_with_progress("Is 7919 prime?", tx, &RunControl::default())
+            .await
+            .unwrap();
+
+        let cells = &result.archive.cells;
+        assert!(!cells.is_empty());
+        for (i, cell) in cells.iter().enumerate() {
+            assert_eq!(cell.niche, Niche::of(&cell.team, &cell.output));
+            assert!(cells[i + 1..].iter().all(|other| other.niche != cell.niche));
+        }
+        // The run's winner is the elite of its own niche.
+        let best = result.archive.ranked()[0];
+        assert_eq!(best.score.total, result.best_score.total);
+    }
+
+    #[tokio::test]
+    async fn resumes_from_checkpoint_at_next_generation() {
+        let path = std::env::temp_dir()
+            .join(format!("emas-test-{}", uuid::Uuid::new_v4()))
+            .join("checkpoint.json");
+        let config = Config {
+            max_generations: 2,
+            checkpoint_path: Some(path.clone()),
+            ..mock_config()
+        };
+        let arena = Arena::new(config).unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        arena.run_with_progress("Is 7919 prime?", tx, &RunControl::default()).await.unwrap();
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.problem, "Is 7919 prime?");
+        assert_eq!(checkpoint.state.next_gen, 2);
+        assert!(checkpoint.state.best_ever.is_some());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+        let mut config = checkpoint.config;
+        config.max_generations = 3;
+        config.checkpoint_path = None;
+        let arena = Arena::new(config).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let result = arena
+            .resume_with_progress(
+                &checkpoint.problem,
+                checkpoint.state,
+                checkpoint.usage,
+                tx,
+                &RunControl::default(),
+            )
+            .await
+            .unwrap();
+
+        let mut started = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let ArenaEvent::GenerationStarted { gen, .. } = event {
+                started.push(gen);
+            }
+        }
+        assert_eq!(started, vec![3]);
+        assert_eq!(result.stop_reason, StopReason::MaxGenerations);
+    }
+
+    #[tokio::test]
+    async fn same_seed_reproduces_the_run() {
+        let run = || async {
+            let config = Config {
+                seed: Some(42),
+                mutation_rate: 1.0,
+                ..mock_config()
+            };
+            let arena = Arena::new(config).unwrap();
+            let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+            arena.run_with_progress("Is 7919 prime?", tx, &RunControl::default()).await.unwrap()
+        };
+
+        let (a, b) = (run().await, run().await);
+        assert_eq!(a.seed, 42);
+        assert_eq!(a.best_team.id, b.best_team.id);
+        let genes = |r: &EvolutionResult| -> Vec<(String, u64)> {
+            r.best_team
+                .agents
+                .iter()
+                .map(|a| (a.genotype.name.clone(), a.genotype.temperature.to_bits()))
+                .collect()
+        };
+        assert_eq!(genes(&a), genes(&b));
+        assert_eq!(a.best_score.total, b.best_score.total);
+    }
+}
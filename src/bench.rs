@@ -0,0 +1,143 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+
+use crate::arena::{Arena, RunControl, StopReason};
+use crate::config::Config;
+
+/// Outcome of one benchmark run.
+#[derive(Debug, Clone)]
+pub struct BenchRun {
+    pub seed: u64,
+    pub best_score: f64,
+    pub generations_run: usize,
+    pub stop_reason: StopReason,
+    pub tokens: u64,
+    pub cost: f64,
+    pub elapsed: Duration,
+}
+
+/// Every run of one problem.
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub problem: String,
+    pub runs: Vec<BenchRun>,
+}
+
+impl BenchResult {
+    pub fn mean_score(&self) -> f64 {
+        mean(self.runs.iter().map(|r| r.best_score))
+    }
+
+    /// Population standard deviation of the best scores.
+    pub fn score_std_dev(&self) -> f64 {
+        let m = self.mean_score();
+        mean(self.runs.iter().map(|r| (r.best_score - m).powi(2))).sqrt()
+    }
+
+    pub fn min_score(&self) -> f64 {
+        self.runs
+            .iter()
+            .map(|r| r.best_score)
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn max_score(&self) -> f64 {
+        self.runs
+            .iter()
+            .map(|r| r.best_score)
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    pub fn mean_generations(&self) -> f64 {
+        mean(self.runs.iter().map(|r| r.generations_run as f64))
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.runs.iter().map(|r| r.tokens).sum()
+    }
+
+    pub fn total_cost(&self) -> f64 {
+        self.runs.iter().map(|r| r.cost).sum()
+    }
+
+    pub fn mean_elapsed(&self) -> Duration {
+        Duration::from_secs_f64(mean(self.runs.iter().map(|r| r.elapsed.as_secs_f64())))
+    }
+}
+
+/// Runs `problem` `runs` times with fresh arenas and no checkpoints.
+///
+/// With a [`Config::seed`], run `i` uses `seed + i` so the whole benchmark
+/// is reproducible; otherwise every run picks its own seed.
+pub async fn bench_problem(config: &Config, problem: &str, runs: usize) -> Result<BenchResult> {
+    if runs == 0 {
+        bail!("Bench needs at least one run per problem");
+    }
+    let mut results = Vec::with_capacity(runs);
+    for i in 0..runs {
+        let mut config = config.clone();
+        config.seed = config.seed.map(|s| s.wrapping_add(i as u64));
+        config.checkpoint_path = None;
+        let arena = Arena::new(config)?;
+
+        let started = Instant::now();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let control = RunControl::default();
+        let run = arena.run_with_progress(problem, tx, &control);
+        let drain = async { while rx.recv().await.is_some() {} };
+        let (result, ()) = tokio::join!(run, drain);
+        let result = result?;
+
+        results.push(BenchRun {
+            seed: result.seed,
+            best_score: result.best_score.total,
+            generations_run: result.generations_run,
+            stop_reason: result.stop_reason,
+            tokens: result.total_tokens,
+            cost: result.total_cost,
+            elapsed: started.elapsed(),
+        });
+    }
+    Ok(BenchResult {
+        problem: problem.to_string(),
+        runs: results,
+    })
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, n) = values.fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Provider;
+
+    #[tokio::test]
+    async fn seeded_bench_offsets_the_seed_per_run() {
+        let config = Config {
+            provider: Provider::Mock,
+            model: "mock".into(),
+            judge_provider: Provider::Mock,
+            judge_model: "mock".into(),
+            population_size: 3,
+            max_generations: 2,
+            fitness_threshold: 10.0,
+            seed: Some(40),
+            ..Config::default()
+        };
+        let bench = bench_problem(&config, "Is 7919 prime?", 3).await.unwrap();
+
+        let seeds: Vec<u64> = bench.runs.iter().map(|r| r.seed).collect();
+        assert_eq!(seeds, vec![40, 41, 42]);
+        assert!(bench.min_score() <= bench.mean_score());
+        assert!(bench.mean_score() <= bench.max_score());
+        assert!(bench.total_tokens() > 0);
+    }
+}
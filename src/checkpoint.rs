@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::arena::{RunState, Usage};
+use crate::config::Config;
+
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// A resumable snapshot of a run, written after every generation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: u32,
+    pub problem: String,
+    pub config: Config,
+    pub state: RunState,
+    /// Usage up to the snapshot, so budgets keep counting after a resume.
+    pub usage: Usage,
+}
+
+/// Borrowing twin of [`Checkpoint`] so saving does not clone the population.
+#[derive(Serialize)]
+struct CheckpointRef<'a> {
+    version: u32,
+    problem: &'a str,
+    config: &'a Config,
+    state: &'a RunState,
+    usage: Usage,
+}
+
+impl Checkpoint {
+    /// Where checkpoints go when no `--checkpoint` path is given.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|d| d.join("emas").join("checkpoints"))
+    }
+
+    /// A fresh file in [`default_dir`](Self::default_dir), named by a new
+    /// run id so concurrent runs never overwrite each other's checkpoint.
+    pub fn default_path() -> Option<PathBuf> {
+        let id = Uuid::new_v4().simple().to_string();
+        Self::default_dir().map(|d| d.join(format!("{}.json", &id[..8])))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("reading checkpoint {}", path.display()))?;
+        let checkpoint: Self = serde_json::from_str(&raw)
+            .with_context(|| format!("parsing checkpoint {}", path.display()))?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            bail!(
+                "Checkpoint {} has version {}, expected {}",
+                path.display(),
+                checkpoint.version,
+                CHECKPOINT_VERSION
+            );
+        }
+        Ok(checkpoint)
+    }
+
+    /// Writes atomically (temp file + rename) so a crash mid-write never
+    /// corrupts the previous checkpoint.
+    pub fn save(
+        path: &Path,
+        problem: &str,
+        config: &Config,
+        state: &RunState,
+        usage: Usage,
+    ) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let json = serde_json::to_string(&CheckpointRef {
+            version: CHECKPOINT_VERSION,
+            problem,
+            config,
+            state,
+            usage,
+        })?;
+        // A temp file of its own, in case another run saves to `path` too.
+        let tmp = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+        fs::write(&tmp, json).with_context(|| format!("writing {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("replacing {}", path.display()))?;
+        Ok(())
+    }
+}
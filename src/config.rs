@@ -1,15 +1,110 @@
 use anyhow::{bail, Result};
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fs;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::archive::DEFAULT_ARCHIVE_PARENT_RATE;
+use crate::checkpoint::Checkpoint;
+use crate::config_file;
+use crate::llm::{PricingTable, ResponseCache, RetryPolicy};
+use crate::problem::{attach, read_source, Problem, DEFAULT_MAX_CONTEXT_BYTES};
+use crate::selection::{SelectionStrategy, DEFAULT_TOURNAMENT_SIZE};
+use crate::species::DEFAULT_SPECIES_THRESHOLD;
+
+/// Share of each generation kept as elites when none is configured.
+pub const DEFAULT_ELITE_FRACTION: f64 = 0.4;
+
+/// Generations between island migrations when none is configured.
+pub const DEFAULT_MIGRATION_INTERVAL: usize = 3;
+
+/// How the CLI reports a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Coloured progress and summary
+    #[default]
+    Human,
+    /// One JSON document with the result once the run ends
+    Json,
+    /// Every arena event as a JSON line while the run progresses
+    Jsonl,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Provider {
     Openai,
     Google,
+    Anthropic,
+    /// Offline, deterministic responses; see [`crate::llm::MockBackend`].
+    Mock,
+}
+
+impl Provider {
+    /// Name the provider's backend is registered under in [`crate::llm`].
+    pub fn key(&self) -> &'static str {
+        match self {
+            Provider::Openai => "openai",
+            Provider::Google => "google",
+            Provider::Anthropic => "anthropic",
+            Provider::Mock => "mock",
+        }
+    }
+
+    pub fn requires_api_key(&self) -> bool {
+        !matches!(self, Provider::Mock)
+    }
+
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            Provider::Openai => "https://api.openai.com/v1",
+            Provider::Google => "https://generativelanguage.googleapis.com/v1beta",
+            Provider::Anthropic => "https://api.anthropic.com/v1",
+            Provider::Mock => "mock://",
+        }
+    }
+
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            Provider::Openai => "gpt-4o-mini",
+            Provider::Google => "gemini-2.0-flash-lite",
+            Provider::Anthropic => "claude-3-5-haiku-latest",
+            Provider::Mock => "mock",
+        }
+    }
+
+    /// Provider-specific API key variable, consulted after `EMAS_API_KEY`.
+    pub fn api_key_env(&self) -> &'static str {
+        match self {
+            Provider::Openai => "OPENAI_API_KEY",
+            Provider::Google => "GOOGLE_API_KEY",
+            Provider::Anthropic => "ANTHROPIC_API_KEY",
+            Provider::Mock => "EMAS_API_KEY",
+        }
+    }
+
+    pub fn model_env(&self) -> &'static str {
+        match self {
+            Provider::Openai => "OPENAI_MODEL",
+            Provider::Google => "GOOGLE_MODEL",
+            Provider::Anthropic => "ANTHROPIC_MODEL",
+            Provider::Mock => "EMAS_MODEL",
+        }
+    }
+
+    /// Lenient parse used for `EMAS_PROVIDER` and other free-form inputs.
+    pub fn parse_loose(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "openai" => Some(Provider::Openai),
+            "google" | "gemini" => Some(Provider::Google),
+            "anthropic" | "claude" => Some(Provider::Anthropic),
+            "mock" => Some(Provider::Mock),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for Provider {
@@ -17,6 +112,8 @@ impl fmt::Display for Provider {
         match self {
             Provider::Openai => write!(f, "OpenAI"),
             Provider::Google => write!(f, "Google Gemini"),
+            Provider::Anthropic => write!(f, "Anthropic"),
+            Provider::Mock => write!(f, "Mock (offline)"),
         }
     }
 }
@@ -76,15 +173,313 @@ impl SavedParams {
 #[command(
     name = "emas",
     version,
-    about = "EMAS - Evolutionary Multi-Agent System\nEvolve AI reasoning through natural selection."
+    about = "EMAS - Evolutionary Multi-Agent System\nEvolve AI reasoning through natural selection.",
+    args_conflicts_with_subcommands = true,
+    arg_required_else_help = true
 )]
 pub struct Cli {
-    #[arg(required_unless_present = "tui")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Launch interactive TUI mode (same as `emas tui`)
+    #[arg(long, hide = true, default_value_t = false)]
+    pub tui: bool,
+
+    /// `emas "<problem>"` is shorthand for `emas run "<problem>"`.
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+impl Cli {
+    /// The subcommand to execute, mapping the bare `emas "<problem>"` and
+    /// `emas --tui` forms onto `run` and `tui`.
+    pub fn into_command(self) -> Command {
+        match self.command {
+            Some(command) => command,
+            None if self.tui => Command::Tui(TuiArgs {
+                input: self.run.input,
+                llm: self.run.llm,
+                evolution: self.run.evolution,
+            }),
+            None => Command::Run(self.run),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Evolve teams on a problem and print the synthesised answer
+    Run(RunArgs),
+    /// Launch the interactive terminal UI
+    Tui(TuiArgs),
+    /// Continue an interrupted run from its last checkpoint
+    Resume {
+        /// Checkpoint written by a previous run
+        checkpoint: PathBuf,
+
+        #[arg(long)]
+        api_key: Option<String>,
+
+        #[arg(long)]
+        judge_api_key: Option<String>,
+
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        output: OutputFormat,
+
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
+    },
+    /// Run problems several times each and summarise scores, tokens and cost
+    Bench(BenchArgs),
+    /// Serve an HTTP API that queues and runs evolution jobs
+    Serve(ServeArgs),
+    /// Summarise a checkpoint (.json) or cassette (.jsonl) without running anything
+    Inspect {
+        /// Checkpoint or cassette file
+        path: PathBuf,
+    },
+    /// Browse and manage finished runs
+    History {
+        #[command(subcommand)]
+        action: HistoryCommand,
+    },
+    /// Inspect configuration files and profiles
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print the configuration a run with these flags would use, API keys masked
+    Show {
+        #[command(flatten)]
+        llm: LlmArgs,
+
+        #[command(flatten)]
+        evolution: EvolutionArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommand {
+    /// List stored runs, newest first
+    List,
+    /// Show a run's winner, scores per generation and synthesis
+    Show {
+        /// Run id (a unique prefix is enough)
+        id: String,
+    },
+    /// Delete a stored run
+    Rm {
+        /// Run id (a unique prefix is enough)
+        id: String,
+    },
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub input: ProblemArgs,
+
+    /// Output format; logs go to stderr when this is json or jsonl
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub output: OutputFormat,
+
+    /// Write a Markdown report of the run, or HTML if PATH ends in .html
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub llm: LlmArgs,
+
+    #[command(flatten)]
+    pub evolution: EvolutionArgs,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct TuiArgs {
+    /// Problem text, problem file and context files to pre-fill the setup form with
+    #[command(flatten)]
+    pub input: ProblemArgs,
+
+    #[command(flatten)]
+    pub llm: LlmArgs,
+
+    #[command(flatten)]
+    pub evolution: EvolutionArgs,
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct BenchArgs {
+    /// Problems to benchmark
+    #[arg(required = true)]
+    pub problems: Vec<String>,
+
+    /// Runs per problem; with --seed, run i uses seed + i
+    #[arg(long, default_value_t = 3)]
+    pub runs: usize,
+
+    #[command(flatten)]
+    pub llm: LlmArgs,
+
+    #[command(flatten)]
+    pub evolution: EvolutionArgs,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub addr: SocketAddr,
+
+    /// Jobs run at the same time
+    #[arg(long, default_value_t = 1)]
+    pub workers: usize,
+
+    /// Jobs that may wait for a worker; further submissions get 503
+    #[arg(long, default_value_t = 16)]
+    pub queue_size: usize,
+
+    /// Finished jobs kept in memory; older ones are dropped
+    #[arg(long, default_value_t = 100)]
+    pub keep_jobs: usize,
+
+    /// Defaults for every job; requests may override evolution settings
+    #[command(flatten)]
+    pub llm: LlmArgs,
+
+    #[command(flatten)]
+    pub evolution: EvolutionArgs,
+}
+
+/// Where the problem comes from and what is attached to it.
+#[derive(Args, Debug, Clone)]
+pub struct ProblemArgs {
+    /// The problem or question to solve ("-" reads it from stdin)
+    #[arg(conflicts_with = "problem_file")]
     pub problem: Option<String>,
 
+    /// Read the problem from a file ("-" for stdin)
+    #[arg(long, value_name = "PATH")]
+    pub problem_file: Option<PathBuf>,
+
+    /// Embed a text file into the problem sent to agents, judges and synthesis (repeatable)
+    #[arg(long, value_name = "FILE")]
+    pub context: Vec<PathBuf>,
+
+    /// Cap on problem plus context bytes; longer input is truncated with a warning
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_CONTEXT_BYTES)]
+    pub max_context_bytes: usize,
+}
+
+impl Default for ProblemArgs {
+    fn default() -> Self {
+        Self {
+            problem: None,
+            problem_file: None,
+            context: Vec::new(),
+            max_context_bytes: DEFAULT_MAX_CONTEXT_BYTES,
+        }
+    }
+}
+
+impl ProblemArgs {
+    /// Reads the problem and embeds the context files; `None` if no problem
+    /// was given.
+    pub fn load(&self) -> Result<Option<Problem>> {
+        read_source(self.problem.as_deref(), self.problem_file.as_deref())?
+            .map(|text| attach(&text, &self.context, self.max_context_bytes))
+            .transpose()
+    }
+}
+
+/// Provider, model and transport options shared by every command that
+/// calls an LLM.
+#[derive(Args, Debug, Clone, Default)]
+#[command(next_help_heading = "Model")]
+pub struct LlmArgs {
+    #[arg(long, value_enum)]
+    pub provider: Option<Provider>,
+
+    #[arg(long)]
+    pub model: Option<String>,
+
+    #[arg(long)]
+    pub api_url: Option<String>,
+
+    #[arg(long)]
+    pub api_key: Option<String>,
+
+    #[arg(long)]
+    pub max_tokens: Option<u32>,
+
+    #[arg(long)]
+    pub judge_model: Option<String>,
+
+    #[arg(long, value_enum)]
+    pub judge_provider: Option<Provider>,
+
+    #[arg(long)]
+    pub judge_api_url: Option<String>,
+
+    #[arg(long)]
+    pub judge_api_key: Option<String>,
+
+    /// Retries per LLM request on 429/5xx/timeouts (0 disables) [default: 3]
+    #[arg(long)]
+    pub max_retries: Option<u32>,
+
+    /// Initial retry backoff in milliseconds, doubled per attempt [default: 500]
+    #[arg(long)]
+    pub retry_base_delay_ms: Option<u64>,
+
+    /// Upper bound for a single retry wait, including Retry-After [default: 60]
+    #[arg(long)]
+    pub retry_max_delay_secs: Option<u64>,
+
+    /// Random +/- fraction applied to each backoff (0.0-1.0) [default: 0.2]
+    #[arg(long)]
+    pub retry_jitter: Option<f64>,
+
+    /// Max LLM requests in flight at once, shared by agents and judges
+    #[arg(long)]
+    pub max_concurrency: Option<usize>,
+
+    /// Max LLM requests per minute across agents and judges
+    #[arg(long)]
+    pub rpm: Option<u32>,
+
+    /// Max LLM tokens (prompt + completion) per minute across agents and judges
+    #[arg(long)]
+    pub tpm: Option<u32>,
+
+    /// Price a model in USD per 1M tokens, e.g. gpt-4o=2.5,10 (repeatable)
+    #[arg(long = "price", value_name = "MODEL=INPUT,OUTPUT")]
+    pub prices: Vec<String>,
+
+    /// Append every LLM request and response to this JSONL cassette
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve LLM responses from a recorded cassette; unrecorded requests fail
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
+    /// Don't read or write the on-disk response cache
     #[arg(long, default_value_t = false)]
-    pub tui: bool,
+    pub no_cache: bool,
+
+    /// Only cache deterministic (temperature 0) calls
+    #[arg(long, default_value_t = false)]
+    pub no_cache_sampled: bool,
+}
 
+/// Evolution parameters, budgets and run bookkeeping.
+#[derive(Args, Debug, Clone, Default)]
+#[command(next_help_heading = "Evolution")]
+pub struct EvolutionArgs {
     #[arg(long)]
     pub population: Option<usize>,
 
@@ -100,20 +495,49 @@ pub struct Cli {
     #[arg(long)]
     pub mutation_rate: Option<f64>,
 
+    /// Share of each generation kept unchanged as elites [default: 0.4]
+    #[arg(long)]
+    pub elite_fraction: Option<f64>,
+
+    /// How crossover parents are chosen [default: truncation]
     #[arg(long, value_enum)]
-    pub provider: Option<Provider>,
+    pub selection: Option<SelectionStrategy>,
 
+    /// Teams per tournament with --selection tournament [default: 3]
     #[arg(long)]
-    pub model: Option<String>,
+    pub tournament_size: Option<usize>,
 
+    /// Split the population into this many independently evolving islands [default: 1]
     #[arg(long)]
-    pub api_url: Option<String>,
+    pub islands: Option<usize>,
 
+    /// Generations between migrations between islands [default: 3]
     #[arg(long)]
-    pub api_key: Option<String>,
+    pub migration_interval: Option<usize>,
 
+    /// Best teams each island sends to the next one per migration [default: 1]
     #[arg(long)]
-    pub max_tokens: Option<u32>,
+    pub migrants: Option<usize>,
+
+    /// Mutation rate per island, e.g. 0.1,0.3,0.6 [default: --mutation-rate everywhere]
+    #[arg(long, value_delimiter = ',', value_name = "RATES")]
+    pub island_mutation_rates: Vec<f64>,
+
+    /// Divide each team's score by the size of its species before choosing parents
+    #[arg(long, overrides_with = "no_fitness_sharing")]
+    pub fitness_sharing: bool,
+
+    /// Choose parents by raw score, even if a config file turns sharing on
+    #[arg(long, overrides_with = "fitness_sharing")]
+    pub no_fitness_sharing: bool,
+
+    /// Genotype distance (0.0-1.0) within which teams share a species [default: 0.3]
+    #[arg(long)]
+    pub species_threshold: Option<f64>,
+
+    /// Chance (0.0-1.0) that a parent is drawn from the MAP-Elites archive [default: 0.0]
+    #[arg(long)]
+    pub archive_parent_rate: Option<f64>,
 
     #[arg(long)]
     pub quality_weight: Option<f64>,
@@ -124,17 +548,33 @@ pub struct Cli {
     #[arg(long)]
     pub efficiency_weight: Option<f64>,
 
+    /// Stop evolving once the run has spent this many USD, then synthesise
     #[arg(long)]
-    pub judge_model: Option<String>,
+    pub max_cost: Option<f64>,
 
-    #[arg(long, value_enum)]
-    pub judge_provider: Option<Provider>,
+    /// Stop evolving once agents and judges have used this many tokens, then synthesise
+    #[arg(long)]
+    pub max_total_tokens: Option<u64>,
+
+    /// Stop evolving after this long, e.g. 90s, 10m or 1h (bare numbers are seconds)
+    #[arg(long, value_parser = parse_duration)]
+    pub max_duration: Option<Duration>,
 
+    /// Seed for the initial population, crossover and mutation (also sent to OpenAI-compatible APIs)
     #[arg(long)]
-    pub judge_api_url: Option<String>,
+    pub seed: Option<u64>,
 
+    /// Checkpoint file written after every generation [default: <data dir>/emas/checkpoints/<run id>.json]
     #[arg(long)]
-    pub judge_api_key: Option<String>,
+    pub checkpoint: Option<PathBuf>,
+
+    /// Don't write checkpoints
+    #[arg(long, default_value_t = false)]
+    pub no_checkpoint: bool,
+
+    /// Apply the [profile.NAME] section of emas.toml / ~/.config/emas/config.toml
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
 
     #[arg(long, default_value_t = false)]
     pub no_save: bool,
@@ -143,10 +583,13 @@ pub struct Cli {
     pub reset_defaults: bool,
 }
 
-#[derive(Debug, Clone)]
+/// Resolved run settings. API keys are never serialised, so checkpoints can
+/// be shared without leaking credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub provider: Provider,
     pub api_base_url: String,
+    #[serde(skip)]
     pub api_key: String,
     pub model: String,
     pub population_size: usize,
@@ -155,186 +598,334 @@ pub struct Config {
     pub fitness_threshold: f64,
     pub mutation_rate: f64,
     pub elite_count: usize,
+    /// Share of the population kept as elites; sets `elite_count`.
+    #[serde(default = "default_elite_fraction")]
+    pub elite_fraction: f64,
+    #[serde(default)]
+    pub selection: SelectionStrategy,
+    #[serde(default = "default_tournament_size")]
+    pub tournament_size: usize,
+    /// Sub-populations evolving side by side; 1 is a single population.
+    #[serde(default = "default_islands")]
+    pub islands: usize,
+    /// Islands exchange teams after every this many generations.
+    #[serde(default = "default_migration_interval")]
+    pub migration_interval: usize,
+    /// Teams each island sends to the next per migration.
+    #[serde(default = "default_migrants")]
+    pub migrants: usize,
+    /// Mutation rate of each island; empty uses `mutation_rate` everywhere.
+    #[serde(default)]
+    pub island_mutation_rates: Vec<f64>,
+    /// Rank teams by score divided by species size when breeding.
+    #[serde(default)]
+    pub fitness_sharing: bool,
+    /// Genotype distance within which teams belong to the same species.
+    #[serde(default = "default_species_threshold")]
+    pub species_threshold: f64,
+    /// Chance that a crossover parent comes from the MAP-Elites archive
+    /// rather than the current generation.
+    #[serde(default = "default_archive_parent_rate")]
+    pub archive_parent_rate: f64,
     pub max_tokens: u32,
     pub quality_weight: f64,
     pub consistency_weight: f64,
     pub efficiency_weight: f64,
     pub judge_provider: Provider,
     pub judge_api_base_url: String,
+    #[serde(skip)]
     pub judge_api_key: String,
     pub judge_model: String,
+    pub retry: RetryPolicy,
+    pub max_concurrency: Option<usize>,
+    pub rpm: Option<u32>,
+    pub tpm: Option<u32>,
+    /// Stream agent replies as [`crate::arena::ArenaEvent::AgentChunk`]s.
+    pub stream: bool,
+    pub pricing: PricingTable,
+    /// Stop evolving once this many USD have been spent.
+    pub max_cost: Option<f64>,
+    /// Stop evolving once agents and judges have used this many tokens.
+    pub max_total_tokens: Option<u64>,
+    /// Stop evolving once the run has taken this long.
+    pub max_duration: Option<Duration>,
+    /// Seeds every random choice in the run; `None` picks one when the
+    /// [`crate::arena::Arena`] is built.
+    pub seed: Option<u64>,
+    /// Cassette to record LLM traffic to.
+    pub record: Option<PathBuf>,
+    /// Cassette to serve LLM responses from instead of calling the provider.
+    pub replay: Option<PathBuf>,
+    /// Directory of the shared response cache; `None` disables caching.
+    pub cache_dir: Option<PathBuf>,
+    /// Cache only temperature-0 calls.
+    #[serde(default)]
+    pub cache_deterministic_only: bool,
+    /// Where to write a checkpoint after every generation; `None` disables it.
+    #[serde(skip)]
+    pub checkpoint_path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    /// Built-in defaults with no API key; mainly useful with [`Provider::Mock`]
+    /// or custom backends passed to [`crate::arena::Arena::with_clients`].
+    fn default() -> Self {
+        let provider = Provider::Openai;
+        Self {
+            provider,
+            api_base_url: provider.default_base_url().into(),
+            api_key: String::new(),
+            model: provider.default_model().into(),
+            population_size: 5,
+            team_size: 3,
+            max_generations: 10,
+            fitness_threshold: 8.5,
+            mutation_rate: 0.3,
+            elite_count: 2,
+            elite_fraction: DEFAULT_ELITE_FRACTION,
+            selection: SelectionStrategy::default(),
+            tournament_size: DEFAULT_TOURNAMENT_SIZE,
+            islands: 1,
+            migration_interval: DEFAULT_MIGRATION_INTERVAL,
+            migrants: 1,
+            island_mutation_rates: Vec::new(),
+            fitness_sharing: false,
+            species_threshold: DEFAULT_SPECIES_THRESHOLD,
+            archive_parent_rate: DEFAULT_ARCHIVE_PARENT_RATE,
+            max_tokens: 1024,
+            quality_weight: 0.50,
+            consistency_weight: 0.30,
+            efficiency_weight: 0.20,
+            judge_provider: provider,
+            judge_api_base_url: provider.default_base_url().into(),
+            judge_api_key: String::new(),
+            judge_model: provider.default_model().into(),
+            retry: RetryPolicy::default(),
+            max_concurrency: None,
+            rpm: None,
+            tpm: None,
+            stream: false,
+            pricing: PricingTable::default(),
+            max_cost: None,
+            max_total_tokens: None,
+            max_duration: None,
+            seed: None,
+            record: None,
+            replay: None,
+            cache_dir: None,
+            cache_deterministic_only: false,
+            checkpoint_path: None,
+        }
+    }
 }
 
 impl Config {
-    pub fn from_cli(cli: &Cli) -> Result<Self> {
-        let saved = if cli.reset_defaults {
+    /// Resolves the run settings. Each setting comes from the first source
+    /// that provides it:
+    ///
+    /// 1. command-line flags
+    /// 2. environment variables (`EMAS_*` and the provider variables)
+    /// 3. the `--profile <name>` section, `[profile.<name>]` in `./emas.toml`
+    ///    and then in `~/.config/emas/config.toml`
+    /// 4. top-level keys of `./emas.toml`
+    /// 5. top-level keys of `~/.config/emas/config.toml`
+    /// 6. `last_params.json`, the parameters of the previous run
+    /// 7. built-in defaults
+    ///
+    /// `last_params.json` is only written when no config file was found, so
+    /// a run never turns profile values into everyday defaults.
+    pub fn from_cli(llm: &LlmArgs, evolution: &EvolutionArgs) -> Result<Self> {
+        let saved = if evolution.reset_defaults {
             SavedParams::delete();
             SavedParams::default()
         } else {
             SavedParams::load()
         };
+        let files = config_file::load(evolution.profile.as_deref())?;
+        let file = files.values.clone().or(saved.into());
 
-        let api_key = cli
-            .api_key
-            .clone()
-            .or_else(|| non_empty_env("EMAS_API_KEY"))
-            .or_else(|| non_empty_env("GOOGLE_API_KEY"))
-            .or_else(|| non_empty_env("OPENAI_API_KEY"))
-            .unwrap_or_default();
-
-        let api_base_url = cli
+        let api_base_url = llm
             .api_url
             .clone()
             .filter(|s| !s.trim().is_empty())
             .or_else(|| non_empty_env("EMAS_API_BASE_URL"))
             .or_else(|| non_empty_env("OPENAI_API_BASE"))
-            .or_else(|| saved.api_url.clone());
+            .or_else(|| file.api_url.clone());
 
-        let provider = if let Some(p) = cli.provider {
+        let provider = if let Some(p) = llm.provider {
             p
         } else if let Some(env_prov) = non_empty_env("EMAS_PROVIDER") {
-            match env_prov.to_lowercase().as_str() {
-                "google" | "gemini" => Provider::Google,
-                _ => Provider::Openai,
-            }
-        } else if let Some(p) = saved.provider {
+            Provider::parse_loose(&env_prov).unwrap_or(Provider::Openai)
+        } else if let Some(p) = file.provider {
             p
         } else if api_base_url
             .as_deref()
-            .map_or(false, |u| u.contains("googleapis.com"))
+            .is_some_and(|u| u.contains("googleapis.com"))
+            || (non_empty_env("GOOGLE_API_KEY").is_some()
+                && non_empty_env("OPENAI_API_KEY").is_none()
+                && non_empty_env("ANTHROPIC_API_KEY").is_none())
         {
             Provider::Google
-        } else if non_empty_env("GOOGLE_API_KEY").is_some()
-            && non_empty_env("OPENAI_API_KEY").is_none()
+        } else if api_base_url
+            .as_deref()
+            .is_some_and(|u| u.contains("anthropic.com"))
+            || (non_empty_env("ANTHROPIC_API_KEY").is_some()
+                && non_empty_env("OPENAI_API_KEY").is_none())
         {
-            Provider::Google
+            Provider::Anthropic
         } else {
             Provider::Openai
         };
 
-        let api_base_url = api_base_url.unwrap_or_else(|| match provider {
-            Provider::Google => {
-                "https://generativelanguage.googleapis.com/v1beta".into()
-            }
-            Provider::Openai => "https://api.openai.com/v1".into(),
-        });
+        let api_key = resolve_api_key(llm.api_key.clone(), provider, file.api_key.clone());
+
+        let api_base_url =
+            api_base_url.unwrap_or_else(|| provider.default_base_url().into());
 
-        let model = cli
+        let model = llm
             .model
             .clone()
             .or_else(|| non_empty_env("EMAS_MODEL"))
-            .or_else(|| match provider {
-                Provider::Google => non_empty_env("GOOGLE_MODEL"),
-                Provider::Openai => non_empty_env("OPENAI_MODEL"),
-            })
-            .or_else(|| saved.model.clone())
-            .unwrap_or_else(|| match provider {
-                Provider::Google => "gemini-2.0-flash-lite".into(),
-                Provider::Openai => "gpt-4o-mini".into(),
-            });
+            .or_else(|| non_empty_env(provider.model_env()))
+            .or_else(|| file.model.clone())
+            .unwrap_or_else(|| provider.default_model().into());
 
-        let population = cli.population.or(saved.population).unwrap_or(5);
-        let team_size = cli.team_size.or(saved.team_size).unwrap_or(3);
-        let generations = cli.generations.or(saved.generations).unwrap_or(10);
-        let threshold = cli.threshold.or(saved.threshold).unwrap_or(8.5);
-        let mutation_rate = cli.mutation_rate.or(saved.mutation_rate).unwrap_or(0.3);
-        let max_tokens = cli.max_tokens.or(saved.max_tokens).unwrap_or(1024);
-        let quality_weight = cli.quality_weight.or(saved.quality_weight).unwrap_or(0.50);
-        let consistency_weight = cli.consistency_weight.or(saved.consistency_weight).unwrap_or(0.30);
-        let efficiency_weight = cli.efficiency_weight.or(saved.efficiency_weight).unwrap_or(0.20);
-
-        if population < 2 {
-            bail!("Population size must be at least 2");
-        }
-        if team_size < 1 {
-            bail!("Team size must be at least 1");
+        let population = evolution.population.or(file.population).unwrap_or(5);
+        let team_size = evolution.team_size.or(file.team_size).unwrap_or(3);
+        let generations = evolution.generations.or(file.generations).unwrap_or(10);
+        let threshold = evolution.threshold.or(file.threshold).unwrap_or(8.5);
+        let mutation_rate = evolution.mutation_rate.or(file.mutation_rate).unwrap_or(0.3);
+        let max_tokens = llm.max_tokens.or(file.max_tokens).unwrap_or(1024);
+        let quality_weight = evolution.quality_weight.or(file.quality_weight).unwrap_or(0.50);
+        let consistency_weight = evolution.consistency_weight.or(file.consistency_weight).unwrap_or(0.30);
+        let efficiency_weight = evolution.efficiency_weight.or(file.efficiency_weight).unwrap_or(0.20);
+
+        let default_retry = RetryPolicy::default();
+        let retry = RetryPolicy {
+            max_retries: llm
+                .max_retries
+                .or(file.max_retries)
+                .unwrap_or(default_retry.max_retries),
+            base_delay: llm
+                .retry_base_delay_ms
+                .or(file.retry_base_delay_ms)
+                .map(Duration::from_millis)
+                .unwrap_or(default_retry.base_delay),
+            max_delay: llm
+                .retry_max_delay_secs
+                .or(file.retry_max_delay_secs)
+                .map(Duration::from_secs)
+                .unwrap_or(default_retry.max_delay),
+            jitter: llm.retry_jitter.or(file.retry_jitter).unwrap_or(default_retry.jitter),
+        };
+        let mut pricing = PricingTable::default();
+        for (model, price) in &file.prices {
+            let price = price
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid price for '{model}' in config file: {e}"))?;
+            pricing = pricing.with_override(model, price);
         }
-        if !(0.0..=1.0).contains(&mutation_rate) {
-            bail!("Mutation rate must be between 0.0 and 1.0");
+        for spec in &llm.prices {
+            let (model, price) = PricingTable::parse_override(spec)
+                .map_err(|e| anyhow::anyhow!("Invalid --price '{spec}': {e}"))?;
+            pricing = pricing.with_override(&model, price);
         }
-        if !(1.0..=10.0).contains(&threshold) {
-            bail!("Fitness threshold must be between 1.0 and 10.0");
+        let max_cost = evolution.max_cost.or(file.max_cost);
+        let max_duration = match (evolution.max_duration, &file.max_duration) {
+            (Some(d), _) => Some(d),
+            (None, Some(s)) => Some(
+                parse_duration(s)
+                    .map_err(|e| anyhow::anyhow!("Invalid max_duration in config file: {e}"))?,
+            ),
+            (None, None) => None,
+        };
+        let replay = llm.replay.clone().or(file.replay.clone());
+        let record = llm.record.clone().or(file.record.clone());
+        if record.is_some() && replay.is_some() {
+            bail!("A cassette cannot be recorded and replayed in the same run");
         }
-        if api_key.is_empty() {
-            bail!(
-                "No API key found. Set EMAS_API_KEY, GOOGLE_API_KEY, or OPENAI_API_KEY \
-                 in your environment or .env file, or pass --api-key on the command line."
-            );
+        if replay.is_none() {
+            ensure_api_key(&api_key, provider)?;
         }
 
-        let elite_count = ((population as f64) * 0.4).ceil() as usize;
-        let elite_count = elite_count.max(1).min(population - 1);
+        // An explicit --elite-fraction beats a file's elite_count.
+        let elite_fraction = evolution
+            .elite_fraction
+            .or(file.elite_fraction)
+            .unwrap_or(DEFAULT_ELITE_FRACTION);
+        let elite_count = match (evolution.elite_fraction, file.elite_count) {
+            (None, Some(count)) => count.max(1).min(population.saturating_sub(1)),
+            _ => Self::elite_count_for(population, elite_fraction),
+        };
+        let selection = evolution.selection.or(file.selection).unwrap_or_default();
+        let tournament_size = evolution
+            .tournament_size
+            .or(file.tournament_size)
+            .unwrap_or(DEFAULT_TOURNAMENT_SIZE);
+        let islands = evolution.islands.or(file.islands).unwrap_or(1);
+        let migration_interval = evolution
+            .migration_interval
+            .or(file.migration_interval)
+            .unwrap_or(DEFAULT_MIGRATION_INTERVAL);
+        let migrants = evolution.migrants.or(file.migrants).unwrap_or(1);
+        let island_mutation_rates = if evolution.island_mutation_rates.is_empty() {
+            file.island_mutation_rates.clone().unwrap_or_default()
+        } else {
+            evolution.island_mutation_rates.clone()
+        };
+        let sharing_flag = match (evolution.fitness_sharing, evolution.no_fitness_sharing) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        };
+        let fitness_sharing = sharing_flag.or(file.fitness_sharing).unwrap_or(false);
+        let species_threshold = evolution
+            .species_threshold
+            .or(file.species_threshold)
+            .unwrap_or(DEFAULT_SPECIES_THRESHOLD);
+        let archive_parent_rate = evolution
+            .archive_parent_rate
+            .or(file.archive_parent_rate)
+            .unwrap_or(DEFAULT_ARCHIVE_PARENT_RATE);
 
-        let judge_provider = cli
+        let judge_provider = llm
             .judge_provider
-            .or(saved.judge_provider)
+            .or(file.judge_provider)
             .unwrap_or(provider);
-        let judge_api_key = cli
-            .judge_api_key
-            .clone()
-            .or_else(|| non_empty_env("EMAS_JUDGE_API_KEY"))
-            .unwrap_or_else(|| api_key.clone());
-        let judge_api_base_url = cli
+        let judge_api_key = resolve_judge_api_key(
+            llm.judge_api_key.clone(),
+            judge_provider,
+            provider,
+            &api_key,
+            file.judge_api_key.clone(),
+        );
+        let judge_api_base_url = llm
             .judge_api_url
             .clone()
             .filter(|s| !s.trim().is_empty())
             .or_else(|| non_empty_env("EMAS_JUDGE_API_BASE_URL"))
-            .or_else(|| saved.judge_api_url.clone())
+            .or_else(|| file.judge_api_url.clone())
             .unwrap_or_else(|| {
                 if judge_provider == provider {
                     api_base_url.clone()
                 } else {
-                    match judge_provider {
-                        Provider::Google => {
-                            "https://generativelanguage.googleapis.com/v1beta".into()
-                        }
-                        Provider::Openai => "https://api.openai.com/v1".into(),
-                    }
+                    judge_provider.default_base_url().into()
                 }
             });
-        let judge_model = cli
+        let judge_model = llm
             .judge_model
             .clone()
             .or_else(|| non_empty_env("EMAS_JUDGE_MODEL"))
-            .or_else(|| saved.judge_model.clone())
-            .unwrap_or_else(|| model.clone());
-
-        if !cli.no_save {
-            let to_save = SavedParams {
-                provider: Some(provider),
-                model: Some(model.clone()),
-                api_url: cli
-                    .api_url
-                    .clone()
-                    .filter(|s| !s.trim().is_empty()),
-                population: Some(population),
-                team_size: Some(team_size),
-                generations: Some(generations),
-                threshold: Some(threshold),
-                mutation_rate: Some(mutation_rate),
-                max_tokens: Some(max_tokens),
-                quality_weight: Some(quality_weight),
-                consistency_weight: Some(consistency_weight),
-                efficiency_weight: Some(efficiency_weight),
-                judge_model: if judge_model != model {
-                    Some(judge_model.clone())
-                } else {
-                    None
-                },
-                judge_provider: if judge_provider != provider {
-                    Some(judge_provider)
+            .or_else(|| file.judge_model.clone())
+            .unwrap_or_else(|| {
+                if judge_provider == provider {
+                    model.clone()
                 } else {
-                    None
-                },
-                judge_api_url: cli
-                    .judge_api_url
-                    .clone()
-                    .filter(|s| !s.trim().is_empty()),
-            };
-            to_save.save();
-        }
+                    judge_provider.default_model().into()
+                }
+            });
 
-        Ok(Config {
+        let config = Config {
             provider,
             api_base_url,
             api_key,
@@ -345,6 +936,16 @@ impl Config {
             fitness_threshold: threshold,
             mutation_rate,
             elite_count,
+            elite_fraction,
+            selection,
+            tournament_size,
+            islands,
+            migration_interval,
+            migrants,
+            island_mutation_rates,
+            fitness_sharing,
+            species_threshold,
+            archive_parent_rate,
             max_tokens,
             quality_weight,
             consistency_weight,
@@ -353,8 +954,265 @@ impl Config {
             judge_api_base_url,
             judge_api_key,
             judge_model,
+            retry,
+            max_concurrency: llm.max_concurrency.or(file.max_concurrency),
+            rpm: llm.rpm.or(file.rpm),
+            tpm: llm.tpm.or(file.tpm),
+            stream: file.stream.unwrap_or(false),
+            pricing,
+            max_cost,
+            max_total_tokens: evolution.max_total_tokens.or(file.max_total_tokens),
+            max_duration,
+            seed: evolution.seed.or(file.seed),
+            record,
+            replay,
+            cache_dir: if llm.no_cache || file.cache == Some(false) {
+                None
+            } else {
+                file.cache_dir.clone().or_else(ResponseCache::default_dir)
+            },
+            cache_deterministic_only: llm.no_cache_sampled || file.cache_sampled == Some(false),
+            checkpoint_path: if evolution.no_checkpoint || file.checkpoints == Some(false) {
+                None
+            } else {
+                evolution
+                    .checkpoint
+                    .clone()
+                    .or_else(|| file.checkpoint.clone())
+                    .or_else(Checkpoint::default_path)
+            },
+        };
+        config.validate()?;
+
+        if !evolution.no_save && files.paths.is_empty() {
+            let to_save = SavedParams {
+                provider: Some(config.provider),
+                model: Some(config.model.clone()),
+                api_url: llm
+                    .api_url
+                    .clone()
+                    .filter(|s| !s.trim().is_empty()),
+                population: Some(config.population_size),
+                team_size: Some(config.team_size),
+                generations: Some(config.max_generations),
+                threshold: Some(config.fitness_threshold),
+                mutation_rate: Some(config.mutation_rate),
+                max_tokens: Some(config.max_tokens),
+                quality_weight: Some(config.quality_weight),
+                consistency_weight: Some(config.consistency_weight),
+                efficiency_weight: Some(config.efficiency_weight),
+                judge_model: if config.judge_model != config.model {
+                    Some(config.judge_model.clone())
+                } else {
+                    None
+                },
+                judge_provider: if config.judge_provider != config.provider {
+                    Some(config.judge_provider)
+                } else {
+                    None
+                },
+                judge_api_url: llm
+                    .judge_api_url
+                    .clone()
+                    .filter(|s| !s.trim().is_empty()),
+            };
+            to_save.save();
+        }
+        Ok(config)
+    }
+
+    /// Elites kept per generation: `fraction` of the population rounded up,
+    /// at least one and leaving room for one child.
+    pub fn elite_count_for(population: usize, fraction: f64) -> usize {
+        ((population as f64) * fraction)
+            .ceil()
+            .max(1.0)
+            .min(population.saturating_sub(1) as f64) as usize
+    }
+
+    /// Rejects out-of-range settings.
+    pub fn validate(&self) -> Result<()> {
+        if self.population_size < 2 {
+            bail!("Population size must be at least 2");
+        }
+        if self.team_size < 1 {
+            bail!("Team size must be at least 1");
+        }
+        if !(0.0..=1.0).contains(&self.mutation_rate) {
+            bail!("Mutation rate must be between 0.0 and 1.0");
+        }
+        if !(self.elite_fraction > 0.0 && self.elite_fraction < 1.0) {
+            bail!("Elite fraction must be between 0.0 and 1.0 (exclusive)");
+        }
+        if self.tournament_size < 1 {
+            bail!("Tournament size must be at least 1");
+        }
+        if self.islands < 1 {
+            bail!("Islands must be at least 1");
+        }
+        if self.islands > 1 {
+            if self.population_size < 2 * self.islands {
+                bail!(
+                    "Population size must be at least 2 per island ({} for {} islands)",
+                    2 * self.islands,
+                    self.islands
+                );
+            }
+            if self.migration_interval < 1 {
+                bail!("Migration interval must be at least 1");
+            }
+            let smallest = self.population_size / self.islands;
+            if self.migrants >= smallest {
+                bail!("Migrants must be fewer than the teams per island ({smallest})");
+            }
+        }
+        if !self.island_mutation_rates.is_empty() {
+            if self.island_mutation_rates.len() != self.islands {
+                bail!(
+                    "Expected {} island mutation rates, got {}",
+                    self.islands,
+                    self.island_mutation_rates.len()
+                );
+            }
+            if self
+                .island_mutation_rates
+                .iter()
+                .any(|r| !(0.0..=1.0).contains(r))
+            {
+                bail!("Island mutation rates must be between 0.0 and 1.0");
+            }
+        }
+        if !(0.0..=1.0).contains(&self.species_threshold) {
+            bail!("Species threshold must be between 0.0 and 1.0");
+        }
+        if !(0.0..=1.0).contains(&self.archive_parent_rate) {
+            bail!("Archive parent rate must be between 0.0 and 1.0");
+        }
+        if !(1.0..=10.0).contains(&self.fitness_threshold) {
+            bail!("Fitness threshold must be between 1.0 and 10.0");
+        }
+        if !(0.0..=1.0).contains(&self.retry.jitter) {
+            bail!("Retry jitter must be between 0.0 and 1.0");
+        }
+        if self.max_cost.is_some_and(|c| c <= 0.0) {
+            bail!("Max cost must be greater than 0");
+        }
+        Ok(())
+    }
+
+    /// Re-resolves the API keys a deserialised config (e.g. from a checkpoint)
+    /// was saved without, using the same flags, variables and config files
+    /// (without a profile) as a fresh run.
+    pub fn restore_api_keys(
+        &mut self,
+        api_key: Option<String>,
+        judge_api_key: Option<String>,
+    ) -> Result<()> {
+        let file = config_file::load(None)?.values;
+        self.api_key = resolve_api_key(api_key, self.provider, file.api_key);
+        if self.replay.is_none() {
+            ensure_api_key(&self.api_key, self.provider)?;
+        }
+        self.judge_api_key = resolve_judge_api_key(
+            judge_api_key,
+            self.judge_provider,
+            self.provider,
+            &self.api_key,
+            file.judge_api_key,
+        );
+        Ok(())
+    }
+}
+
+/// `explicit` (the flag), then the environment, then `from_file`.
+fn resolve_api_key(explicit: Option<String>, provider: Provider, from_file: Option<String>) -> String {
+    explicit
+        .or_else(|| non_empty_env("EMAS_API_KEY"))
+        .or_else(|| non_empty_env(provider.api_key_env()))
+        .or_else(|| non_empty_env("GOOGLE_API_KEY"))
+        .or_else(|| non_empty_env("OPENAI_API_KEY"))
+        .or_else(|| non_empty_env("ANTHROPIC_API_KEY"))
+        .or(from_file)
+        .unwrap_or_default()
+}
+
+fn resolve_judge_api_key(
+    explicit: Option<String>,
+    judge_provider: Provider,
+    provider: Provider,
+    api_key: &str,
+    from_file: Option<String>,
+) -> String {
+    explicit
+        .or_else(|| non_empty_env("EMAS_JUDGE_API_KEY"))
+        .or_else(|| {
+            (judge_provider != provider)
+                .then(|| non_empty_env(judge_provider.api_key_env()))
+                .flatten()
         })
+        .or(from_file)
+        .unwrap_or_else(|| api_key.to_string())
+}
+
+fn ensure_api_key(api_key: &str, provider: Provider) -> Result<()> {
+    if api_key.is_empty() && provider.requires_api_key() {
+        bail!(
+            "No API key found. Set EMAS_API_KEY, GOOGLE_API_KEY, OPENAI_API_KEY, or \
+             ANTHROPIC_API_KEY in your environment or .env file, or pass --api-key \
+             on the command line."
+        );
     }
+    Ok(())
+}
+
+/// Parses `90`, `90s`, `10m` or `1h` into a [`Duration`].
+pub(crate) fn parse_duration(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(idx) => s.split_at(idx),
+        None => (s, "s"),
+    };
+    let value: f64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid duration '{s}'"))?;
+    let secs = match unit.trim() {
+        "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        other => bail!("unknown duration unit '{other}' (use s, m or h)"),
+    };
+    if secs <= 0.0 {
+        bail!("duration must be greater than 0");
+    }
+    Duration::try_from_secs_f64(secs).map_err(|_| anyhow::anyhow!("duration '{s}' is too long"))
+}
+
+fn default_elite_fraction() -> f64 {
+    DEFAULT_ELITE_FRACTION
+}
+
+fn default_tournament_size() -> usize {
+    DEFAULT_TOURNAMENT_SIZE
+}
+
+fn default_islands() -> usize {
+    1
+}
+
+fn default_migration_interval() -> usize {
+    DEFAULT_MIGRATION_INTERVAL
+}
+
+fn default_migrants() -> usize {
+    1
+}
+
+fn default_species_threshold() -> f64 {
+    DEFAULT_SPECIES_THRESHOLD
+}
+
+fn default_archive_parent_rate() -> f64 {
+    DEFAULT_ARCHIVE_PARENT_RATE
 }
 
 fn non_empty_env(key: &str) -> Option<String> {
@@ -0,0 +1,447 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint::Checkpoint;
+use crate::config::{Config, Provider, SavedParams};
+use crate::selection::SelectionStrategy;
+
+/// Project-local config file, looked up in the working directory.
+pub const PROJECT_FILE: &str = "emas.toml";
+
+/// Settings an `emas.toml`, `config.toml` or `[profile.<name>]` section may
+/// set. Keys are named after the CLI flags; every one is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub provider: Option<Provider>,
+    pub model: Option<String>,
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub judge_provider: Option<Provider>,
+    pub judge_model: Option<String>,
+    pub judge_api_url: Option<String>,
+    pub judge_api_key: Option<String>,
+    pub max_retries: Option<u32>,
+    pub retry_base_delay_ms: Option<u64>,
+    pub retry_max_delay_secs: Option<u64>,
+    pub retry_jitter: Option<f64>,
+    pub max_concurrency: Option<usize>,
+    pub rpm: Option<u32>,
+    pub tpm: Option<u32>,
+    /// `true` streams agent replies as `agent_chunk` events; the TUI always does.
+    pub stream: Option<bool>,
+    /// Model name to `"INPUT,OUTPUT"` USD per 1M tokens, as for `--price`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub prices: BTreeMap<String, String>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    /// `false` disables the response cache, like `--no-cache`.
+    pub cache: Option<bool>,
+    pub cache_dir: Option<PathBuf>,
+    /// `false` caches only temperature-0 calls, like `--no-cache-sampled`.
+    pub cache_sampled: Option<bool>,
+    pub population: Option<usize>,
+    pub team_size: Option<usize>,
+    pub generations: Option<usize>,
+    pub threshold: Option<f64>,
+    pub mutation_rate: Option<f64>,
+    pub elite_count: Option<usize>,
+    pub elite_fraction: Option<f64>,
+    pub selection: Option<SelectionStrategy>,
+    pub tournament_size: Option<usize>,
+    pub islands: Option<usize>,
+    pub migration_interval: Option<usize>,
+    pub migrants: Option<usize>,
+    pub island_mutation_rates: Option<Vec<f64>>,
+    pub fitness_sharing: Option<bool>,
+    pub species_threshold: Option<f64>,
+    pub archive_parent_rate: Option<f64>,
+    pub quality_weight: Option<f64>,
+    pub consistency_weight: Option<f64>,
+    pub efficiency_weight: Option<f64>,
+    pub max_cost: Option<f64>,
+    pub max_total_tokens: Option<u64>,
+    /// e.g. `"90s"`, `"10m"` or `"1h"`.
+    pub max_duration: Option<String>,
+    pub seed: Option<u64>,
+    pub checkpoint: Option<PathBuf>,
+    /// `false` disables checkpoints, like `--no-checkpoint`.
+    pub checkpoints: Option<bool>,
+}
+
+macro_rules! merge_fields {
+    ($high:ident, $low:ident, $($field:ident),* $(,)?) => {
+        FileConfig {
+            $($field: $high.$field.or($low.$field),)*
+            prices: {
+                let mut prices = $low.prices;
+                prices.extend($high.prices);
+                prices
+            },
+        }
+    };
+}
+
+impl FileConfig {
+    /// Field-wise merge where `self` wins over `lower`. Price tables are
+    /// merged per model.
+    pub fn or(self, lower: FileConfig) -> FileConfig {
+        merge_fields!(
+            self,
+            lower,
+            provider,
+            model,
+            api_url,
+            api_key,
+            max_tokens,
+            judge_provider,
+            judge_model,
+            judge_api_url,
+            judge_api_key,
+            max_retries,
+            retry_base_delay_ms,
+            retry_max_delay_secs,
+            retry_jitter,
+            max_concurrency,
+            rpm,
+            tpm,
+            stream,
+            record,
+            replay,
+            cache,
+            cache_dir,
+            cache_sampled,
+            population,
+            team_size,
+            generations,
+            threshold,
+            mutation_rate,
+            elite_count,
+            elite_fraction,
+            selection,
+            tournament_size,
+            islands,
+            migration_interval,
+            migrants,
+            island_mutation_rates,
+            fitness_sharing,
+            species_threshold,
+            archive_parent_rate,
+            quality_weight,
+            consistency_weight,
+            efficiency_weight,
+            max_cost,
+            max_total_tokens,
+            max_duration,
+            seed,
+            checkpoint,
+            checkpoints,
+        )
+    }
+
+    /// The settings that reproduce `config`, without API keys. This is what
+    /// `emas config show` prints, so it can be pasted into a file; see
+    /// [`masked_keys`] for the keys themselves.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            provider: Some(config.provider),
+            model: Some(config.model.clone()),
+            api_url: Some(config.api_base_url.clone()),
+            api_key: None,
+            max_tokens: Some(config.max_tokens),
+            judge_provider: Some(config.judge_provider),
+            judge_model: Some(config.judge_model.clone()),
+            judge_api_url: Some(config.judge_api_base_url.clone()),
+            judge_api_key: None,
+            max_retries: Some(config.retry.max_retries),
+            retry_base_delay_ms: Some(config.retry.base_delay.as_millis() as u64),
+            retry_max_delay_secs: Some(config.retry.max_delay.as_secs()),
+            retry_jitter: Some(config.retry.jitter),
+            max_concurrency: config.max_concurrency,
+            rpm: config.rpm,
+            tpm: config.tpm,
+            stream: Some(config.stream),
+            prices: config
+                .pricing
+                .overrides()
+                .iter()
+                .map(|(model, p)| {
+                    (
+                        model.clone(),
+                        format!("{},{}", p.input_per_mtok, p.output_per_mtok),
+                    )
+                })
+                .collect(),
+            record: config.record.clone(),
+            replay: config.replay.clone(),
+            cache: Some(config.cache_dir.is_some()),
+            cache_dir: config.cache_dir.clone(),
+            cache_sampled: Some(!config.cache_deterministic_only),
+            population: Some(config.population_size),
+            team_size: Some(config.team_size),
+            generations: Some(config.max_generations),
+            threshold: Some(config.fitness_threshold),
+            mutation_rate: Some(config.mutation_rate),
+            elite_count: Some(config.elite_count),
+            elite_fraction: Some(config.elite_fraction),
+            selection: Some(config.selection),
+            tournament_size: Some(config.tournament_size),
+            islands: Some(config.islands),
+            migration_interval: Some(config.migration_interval),
+            migrants: Some(config.migrants),
+            island_mutation_rates: Some(config.island_mutation_rates.clone())
+                .filter(|rates| !rates.is_empty()),
+            fitness_sharing: Some(config.fitness_sharing),
+            species_threshold: Some(config.species_threshold),
+            archive_parent_rate: Some(config.archive_parent_rate),
+            quality_weight: Some(config.quality_weight),
+            consistency_weight: Some(config.consistency_weight),
+            efficiency_weight: Some(config.efficiency_weight),
+            max_cost: config.max_cost,
+            max_total_tokens: config.max_total_tokens,
+            max_duration: config.max_duration.map(|d| format!("{}s", d.as_secs_f64())),
+            seed: config.seed,
+            // A per-run default path would pin every later run to one file.
+            checkpoint: config
+                .checkpoint_path
+                .clone()
+                .filter(|path| path.parent() != Checkpoint::default_dir().as_deref()),
+            checkpoints: Some(config.checkpoint_path.is_some()),
+        }
+    }
+}
+
+impl From<SavedParams> for FileConfig {
+    fn from(saved: SavedParams) -> Self {
+        Self {
+            provider: saved.provider,
+            model: saved.model,
+            api_url: saved.api_url,
+            population: saved.population,
+            team_size: saved.team_size,
+            generations: saved.generations,
+            threshold: saved.threshold,
+            mutation_rate: saved.mutation_rate,
+            max_tokens: saved.max_tokens,
+            quality_weight: saved.quality_weight,
+            consistency_weight: saved.consistency_weight,
+            efficiency_weight: saved.efficiency_weight,
+            judge_model: saved.judge_model,
+            judge_provider: saved.judge_provider,
+            judge_api_url: saved.judge_api_url,
+            ..Self::default()
+        }
+    }
+}
+
+/// Comment lines describing the resolved API keys, masked, with the
+/// environment variable that supplies each one. `emas config show` prints
+/// these instead of key fields, so pasting its output never stores a masked
+/// key as the real one.
+pub fn masked_keys(config: &Config) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut describe = |field: &str, key: &str, env: &str| {
+        if let Some(masked) = mask_secret(key) {
+            lines.push(format!("# {field}: {masked} (left out; supply it via {env})"));
+        }
+    };
+    describe("api_key", &config.api_key, config.provider.api_key_env());
+    describe("judge_api_key", &config.judge_api_key, "EMAS_JUDGE_API_KEY");
+    lines
+}
+
+/// `"sk-...wxyz"`-style preview of a key; `None` when there is no key.
+fn mask_secret(secret: &str) -> Option<String> {
+    let chars: Vec<char> = secret.chars().collect();
+    match chars.len() {
+        0 => None,
+        1..=12 => Some("****".into()),
+        n => Some(format!(
+            "{}...{}",
+            chars[..3].iter().collect::<String>(),
+            chars[n - 4..].iter().collect::<String>()
+        )),
+    }
+}
+
+/// One parsed config file: top-level settings plus named profiles.
+#[derive(Debug, Clone, Default)]
+struct ConfigFile {
+    base: FileConfig,
+    profiles: BTreeMap<String, FileConfig>,
+}
+
+impl ConfigFile {
+    fn read(path: &Path) -> Result<Option<Self>> {
+        let raw = match fs::read_to_string(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        Self::parse(&raw)
+            .with_context(|| format!("parsing {}", path.display()))
+            .map(Some)
+    }
+
+    fn parse(raw: &str) -> Result<Self> {
+        let mut table: toml::Table = raw.parse()?;
+        let profiles = match table.remove("profile") {
+            Some(profiles) => profiles.try_into()?,
+            None => BTreeMap::new(),
+        };
+        Ok(Self {
+            base: toml::Value::Table(table).try_into()?,
+            profiles,
+        })
+    }
+}
+
+/// Settings gathered from the config files, merged by precedence.
+#[derive(Debug, Clone, Default)]
+pub struct LoadedFiles {
+    pub values: FileConfig,
+    /// Files that existed and were read, highest precedence first.
+    pub paths: Vec<PathBuf>,
+}
+
+/// `~/.config/emas/config.toml` (or the platform equivalent).
+pub fn user_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("emas").join("config.toml"))
+}
+
+/// Loads `./emas.toml` and the user config, applying `profile` if given.
+pub fn load(profile: Option<&str>) -> Result<LoadedFiles> {
+    load_from(
+        Some(Path::new(PROJECT_FILE)),
+        user_path().as_deref(),
+        profile,
+    )
+}
+
+/// Merges, highest precedence first: the profile section of the project
+/// file, the profile section of the user file, the project file's top
+/// level, the user file's top level. An unknown profile is an error.
+pub fn load_from(
+    project: Option<&Path>,
+    user: Option<&Path>,
+    profile: Option<&str>,
+) -> Result<LoadedFiles> {
+    let mut files = Vec::new();
+    let mut paths = Vec::new();
+    for path in [project, user].into_iter().flatten() {
+        if let Some(file) = ConfigFile::read(path)? {
+            files.push(file);
+            paths.push(path.to_path_buf());
+        }
+    }
+
+    let mut values = FileConfig::default();
+    if let Some(name) = profile {
+        let sections: Vec<&FileConfig> =
+            files.iter().filter_map(|f| f.profiles.get(name)).collect();
+        if sections.is_empty() {
+            let known: BTreeSet<&str> = files
+                .iter()
+                .flat_map(|f| f.profiles.keys().map(String::as_str))
+                .collect();
+            bail!(
+                "Unknown profile '{name}'{}",
+                if known.is_empty() {
+                    " (no [profile.<name>] sections found)".to_string()
+                } else {
+                    let known: Vec<&str> = known.into_iter().collect();
+                    format!(" (available: {})", known.join(", "))
+                }
+            );
+        }
+        for section in sections {
+            values = values.or(section.clone());
+        }
+    }
+    for file in files {
+        values = values.or(file.base);
+    }
+    Ok(LoadedFiles { values, paths })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_beats_project_which_beats_user_file() {
+        let dir = std::env::temp_dir().join(format!("emas-config-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let project = dir.join("emas.toml");
+        let user = dir.join("config.toml");
+        fs::write(
+            &project,
+            r#"
+population = 8
+model = "gpt-4o"
+
+[prices]
+"gpt-4o" = "2.5,10"
+
+[profile.cheap]
+model = "gpt-4o-mini"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            &user,
+            r#"
+population = 4
+generations = 3
+provider = "openai"
+
+[profile.cheap]
+generations = 2
+max_cost = 0.5
+"#,
+        )
+        .unwrap();
+
+        let base = load_from(Some(&project), Some(&user), None).unwrap();
+        assert_eq!(base.paths, vec![project.clone(), user.clone()]);
+        assert_eq!(base.values.population, Some(8));
+        assert_eq!(base.values.generations, Some(3));
+        assert_eq!(base.values.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(base.values.provider, Some(Provider::Openai));
+        assert_eq!(base.values.prices["gpt-4o"], "2.5,10");
+
+        let cheap = load_from(Some(&project), Some(&user), Some("cheap")).unwrap();
+        assert_eq!(cheap.values.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(cheap.values.generations, Some(2));
+        assert_eq!(cheap.values.max_cost, Some(0.5));
+        assert_eq!(cheap.values.population, Some(8));
+
+        let err = load_from(Some(&project), Some(&user), Some("fast")).unwrap_err();
+        assert!(err.to_string().contains("available: cheap"));
+
+        fs::write(&project, "populaton = 8\n").unwrap();
+        assert!(load_from(Some(&project), None, None).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn shown_config_leaves_out_api_keys() {
+        let config = Config {
+            api_key: "sk-live-1234567890abcd".into(),
+            ..Config::default()
+        };
+        let shown = toml::to_string_pretty(&FileConfig::from_config(&config)).unwrap();
+        assert!(!shown.contains("api_key"));
+        assert!(!shown.contains("abcd"));
+        assert_eq!(
+            masked_keys(&config),
+            vec!["# api_key: sk-...abcd (left out; supply it via OPENAI_API_KEY)"]
+        );
+    }
+}
@@ -1,12 +1,17 @@
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::agent::Agent;
+use crate::archive::EliteArchive;
 use crate::config::Config;
 use crate::genotype::{Genotype, ReasoningStrategy, MUTATION_MODIFIERS};
 use crate::knowledge::KnowledgeBase;
 use crate::orchestrator::FitnessScore;
+use crate::selection::SelectionStrategy;
+use crate::species;
 use crate::team::{Team, TeamOutput};
 
 const GREEK: &[&str] = &[
@@ -24,11 +29,27 @@ fn team_name(index: usize, generation: usize) -> String {
     }
 }
 
+/// Derives an independent RNG for one team, so its draws depend only on the
+/// run seed and its position, not on how many draws other teams made.
+fn team_rng(rng: &mut impl Rng) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(rng.gen())
+}
+
+/// A v4 UUID drawn from `rng` rather than the OS, so seeded runs reproduce ids.
+fn random_id(rng: &mut impl Rng) -> Uuid {
+    uuid::Builder::from_random_bytes(rng.gen()).into_uuid()
+}
+
 pub fn create_initial_population(config: &Config, rng: &mut impl Rng) -> Vec<Team> {
     let templates = Genotype::templates();
     let mut teams = Vec::with_capacity(config.population_size);
+    let islands = island_sizes(config.population_size, config.islands)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(island, size)| std::iter::repeat_n(island, size));
 
-    for team_idx in 0..config.population_size {
+    for (team_idx, island) in islands.enumerate() {
+        let rng = &mut team_rng(rng);
         let mut indices: Vec<usize> = (0..templates.len()).collect();
         indices.shuffle(rng);
 
@@ -41,21 +62,28 @@ pub fn create_initial_population(config: &Config, rng: &mut impl Rng) -> Vec<Tea
         let mut agents: Vec<Agent> = (0..standard_count)
             .map(|i| {
                 let genotype = templates[indices[i % indices.len()]].clone();
-                Agent::new(genotype)
+                Agent {
+                    id: random_id(rng),
+                    genotype,
+                }
             })
             .collect();
 
         if config.team_size > 1 {
             let mut red = Genotype::new("Red Team Analyst", ReasoningStrategy::RedTeam, 0.5);
             red.is_red_team = true;
-            agents.push(Agent::new(red));
+            agents.push(Agent {
+                id: random_id(rng),
+                genotype: red,
+            });
         }
 
         teams.push(Team {
-            id: Uuid::new_v4(),
+            id: random_id(rng),
             name: team_name(team_idx, 0),
             agents,
             generation: 0,
+            island,
         });
     }
 
@@ -68,6 +96,136 @@ pub struct ScoredTeam {
     pub score: FitnessScore,
 }
 
+/// A team on the Pareto front over quality, consistency and efficiency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParetoMember {
+    pub team: Team,
+    pub output: TeamOutput,
+    pub score: FitnessScore,
+}
+
+fn objectives(score: &FitnessScore) -> [f64; 3] {
+    [score.quality, score.consistency, score.efficiency]
+}
+
+/// `a` is at least as good as `b` on every objective and better on one.
+pub fn dominates(a: &FitnessScore, b: &FitnessScore) -> bool {
+    let (a, b) = (objectives(a), objectives(b));
+    a.iter().zip(&b).all(|(x, y)| x >= y) && a.iter().zip(&b).any(|(x, y)| x > y)
+}
+
+/// NSGA-II's fast non-dominated sort: indices into `scores` grouped by
+/// front, the non-dominated front first.
+pub fn non_dominated_fronts(scores: &[&FitnessScore]) -> Vec<Vec<usize>> {
+    let n = scores.len();
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut domination_count = vec![0usize; n];
+    for i in 0..n {
+        for j in 0..n {
+            if dominates(scores[i], scores[j]) {
+                dominated_by[i].push(j);
+            } else if dominates(scores[j], scores[i]) {
+                domination_count[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut current: Vec<usize> = (0..n).filter(|&i| domination_count[i] == 0).collect();
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &i in &current {
+            for &j in &dominated_by[i] {
+                domination_count[j] -= 1;
+                if domination_count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        fronts.push(current);
+        current = next;
+    }
+    fronts
+}
+
+/// Crowding distance of every member of `front`, in `front` order. The
+/// extremes of each objective are infinitely far from their neighbours.
+pub fn crowding_distances(front: &[usize], scores: &[&FitnessScore]) -> Vec<f64> {
+    let mut distances = vec![0.0; front.len()];
+    if front.len() <= 2 {
+        return vec![f64::INFINITY; front.len()];
+    }
+    for objective in 0..3 {
+        let value = |k: usize| objectives(scores[front[k]])[objective];
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| value(a).total_cmp(&value(b)));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distances[first] = f64::INFINITY;
+        distances[last] = f64::INFINITY;
+        let range = value(last) - value(first);
+        if range <= 0.0 {
+            continue;
+        }
+        for w in order.windows(3) {
+            distances[w[1]] += (value(w[2]) - value(w[0])) / range;
+        }
+    }
+    distances
+}
+
+/// Reorders `scored` by NSGA-II's crowded comparison: lower front first,
+/// then the more isolated team within a front. Ties keep their order.
+pub fn sort_by_crowded_comparison(scored: &mut Vec<ScoredTeam>) {
+    let scores: Vec<&FitnessScore> = scored.iter().map(|s| &s.score).collect();
+    let mut keys = vec![(0usize, 0.0f64); scored.len()];
+    for (rank, front) in non_dominated_fronts(&scores).iter().enumerate() {
+        for (&i, distance) in front.iter().zip(crowding_distances(front, &scores)) {
+            keys[i] = (rank, distance);
+        }
+    }
+    let mut keyed: Vec<((usize, f64), ScoredTeam)> =
+        keys.into_iter().zip(scored.drain(..)).collect();
+    keyed.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)));
+    scored.extend(keyed.into_iter().map(|(_, team)| team));
+}
+
+/// Reorders `scored`, sorted best first, by fitness shared within species
+/// (see [`species::shared_fitness`]) and returns the shared scores in the new
+/// order. Ties keep their order.
+pub fn sort_by_shared_fitness(scored: &mut Vec<ScoredTeam>, threshold: f64) -> Vec<f64> {
+    let teams: Vec<&Team> = scored.iter().map(|s| &s.team).collect();
+    let totals: Vec<f64> = scored.iter().map(|s| s.score.total).collect();
+    let shared = species::shared_fitness(&totals, &species::speciate(&teams, threshold));
+    let mut keyed: Vec<(f64, ScoredTeam)> = shared.into_iter().zip(scored.drain(..)).collect();
+    keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    let (shared, teams): (Vec<f64>, Vec<ScoredTeam>) = keyed.into_iter().unzip();
+    scored.extend(teams);
+    shared
+}
+
+/// Adds the teams of `scored` that no member of `front` dominates, and drops
+/// the members they dominate. Teams already on the front and exact ties
+/// with a member are skipped.
+pub fn update_pareto_front(front: &mut Vec<ParetoMember>, scored: &[ScoredTeam]) {
+    for candidate in scored {
+        let known = front.iter().any(|m| {
+            m.team.id == candidate.team.id
+                || dominates(&m.score, &candidate.score)
+                || objectives(&m.score) == objectives(&candidate.score)
+        });
+        if known {
+            continue;
+        }
+        front.retain(|m| !dominates(&candidate.score, &m.score));
+        front.push(ParetoMember {
+            team: candidate.team.clone(),
+            output: candidate.output.clone(),
+            score: candidate.score.clone(),
+        });
+    }
+    front.sort_by(|a, b| b.score.total.total_cmp(&a.score.total));
+}
+
 pub struct PopulationMember {
     pub team: Team,
     pub cached: Option<(TeamOutput, FitnessScore)>,
@@ -109,20 +267,27 @@ pub fn crossover(
             (None, None) => Genotype::random(rng),
         };
 
-        agents.push(Agent::new(genotype));
+        agents.push(Agent {
+            id: random_id(rng),
+            genotype,
+        });
     }
 
     if team_size > 1 {
         let mut red = Genotype::new("Red Team Analyst", ReasoningStrategy::RedTeam, 0.5);
         red.is_red_team = true;
-        agents.push(Agent::new(red));
+        agents.push(Agent {
+            id: random_id(rng),
+            genotype: red,
+        });
     }
 
     Team {
-        id: Uuid::new_v4(),
+        id: random_id(rng),
         name: team_name(child_index, generation),
         agents,
         generation,
+        island: parent_a.island,
     }
 }
 
@@ -178,6 +343,7 @@ pub fn next_generation(
     config: &Config,
     generation: usize,
     knowledge: &KnowledgeBase,
+    archive: &EliteArchive,
     rng: &mut impl Rng,
 ) -> Vec<PopulationMember> {
     let best_feedback: Option<String> = scored
@@ -191,9 +357,23 @@ pub fn next_generation(
             .partial_cmp(&a.score.total)
             .unwrap_or(std::cmp::Ordering::Equal)
     });
+    let mut ranked: Vec<f64> = scored.iter().map(|s| s.score.total).collect();
+    if config.fitness_sharing {
+        ranked = sort_by_shared_fitness(scored, config.species_threshold);
+    }
+    if config.selection == SelectionStrategy::Nsga2 {
+        // NSGA-II picks by position alone; sharing only breaks its ties.
+        sort_by_crowded_comparison(scored);
+        ranked = scored.iter().map(|s| s.score.total).collect();
+    }
 
-    let elite_scored: Vec<ScoredTeam> = scored.drain(..config.elite_count.min(scored.len())).collect();
-    let elite_teams: Vec<Team> = elite_scored.iter().map(|s| s.team.clone()).collect();
+    let elite_count = config.elite_count.min(scored.len());
+    let parents: Vec<Team> = scored.iter().map(|s| s.team.clone()).collect();
+    if config.fitness_sharing && config.selection != SelectionStrategy::Nsga2 {
+        // Sharing steers breeding only; elites stay the best teams by raw score.
+        scored.sort_by(|a, b| b.score.total.total_cmp(&a.score.total));
+    }
+    let elite_scored: Vec<ScoredTeam> = scored.drain(..elite_count).collect();
 
     let mut next_pop: Vec<PopulationMember> = Vec::with_capacity(config.population_size);
 
@@ -215,8 +395,20 @@ pub fn next_generation(
 
     let mut child_idx = next_pop.len();
     while next_pop.len() < config.population_size {
-        let pa = &elite_teams[rng.gen_range(0..elite_teams.len())];
-        let pb = &elite_teams[rng.gen_range(0..elite_teams.len())];
+        let rng = &mut team_rng(rng);
+        let mut pick = || {
+            if config.archive_parent_rate > 0.0
+                && !archive.is_empty()
+                && rng.gen_bool(config.archive_parent_rate)
+            {
+                return &archive.cells[rng.gen_range(0..archive.len())].team;
+            }
+            let idx = config
+                .selection
+                .pick(&ranked, elite_count, config.tournament_size, rng);
+            &parents[idx]
+        };
+        let (pa, pb) = (pick(), pick());
 
         let mut child = crossover(pa, pb, config.team_size, generation, child_idx, rng);
         mutate(
@@ -235,3 +427,329 @@ pub fn next_generation(
 
     next_pop
 }
+
+/// Teams per island when `population` is split `islands` ways; the first
+/// islands take the remainder.
+pub fn island_sizes(population: usize, islands: usize) -> Vec<usize> {
+    let islands = islands.max(1);
+    (0..islands)
+        .map(|i| population / islands + usize::from(i < population % islands))
+        .collect()
+}
+
+/// The settings island `island` evolves with: its share of the population
+/// and elites, and its own mutation rate if one is configured.
+pub fn island_config(config: &Config, island: usize) -> Config {
+    let mut island_config = config.clone();
+    if config.islands <= 1 {
+        return island_config;
+    }
+    let size = island_sizes(config.population_size, config.islands)[island];
+    island_config.population_size = size;
+    island_config.elite_count = Config::elite_count_for(size, config.elite_fraction);
+    if let Some(&rate) = config.island_mutation_rates.get(island) {
+        island_config.mutation_rate = rate;
+    }
+    island_config
+}
+
+/// A team copied from one island to the next.
+#[derive(Debug, Clone, Serialize)]
+pub struct Migration {
+    pub team: String,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Breeds the next generation. With islands, each one is bred on its own,
+/// after every `migration_interval` generations each island's best
+/// `migrants` teams replace the worst of the next island in a ring.
+pub fn next_generation_islands(
+    scored: &mut Vec<ScoredTeam>,
+    config: &Config,
+    generation: usize,
+    knowledge: &KnowledgeBase,
+    archive: &EliteArchive,
+    rng: &mut impl Rng,
+) -> (Vec<PopulationMember>, Vec<Migration>) {
+    if config.islands <= 1 {
+        return (
+            next_generation(scored, config, generation, knowledge, archive, rng),
+            Vec::new(),
+        );
+    }
+
+    // `scored` arrives best first, and each island keeps that order.
+    let mut islands: Vec<Vec<ScoredTeam>> = (0..config.islands).map(|_| Vec::new()).collect();
+    for st in scored.drain(..) {
+        let island = st.team.island.min(config.islands - 1);
+        islands[island].push(st);
+    }
+
+    let mut migrations = Vec::new();
+    if generation.is_multiple_of(config.migration_interval.max(1)) {
+        let emigrants: Vec<Vec<ScoredTeam>> = islands
+            .iter()
+            .map(|island| {
+                island
+                    .iter()
+                    .take(config.migrants)
+                    .map(|st| ScoredTeam {
+                        team: st.team.clone(),
+                        output: st.output.clone(),
+                        score: st.score.clone(),
+                    })
+                    .collect()
+            })
+            .collect();
+        for (from, group) in emigrants.into_iter().enumerate() {
+            let to = (from + 1) % config.islands;
+            let island = &mut islands[to];
+            island.truncate(island.len().saturating_sub(group.len()));
+            for mut st in group {
+                migrations.push(Migration {
+                    team: st.team.name.clone(),
+                    from,
+                    to,
+                });
+                st.team.id = random_id(rng);
+                st.team.island = to;
+                island.push(st);
+            }
+            island.sort_by(|a, b| b.score.total.total_cmp(&a.score.total));
+        }
+    }
+
+    let mut next_pop = Vec::with_capacity(config.population_size);
+    for (island, mut island_scored) in islands.into_iter().enumerate() {
+        let island_config = island_config(config, island);
+        // Archive parents would cross islands outside of migrations.
+        let island_archive = EliteArchive {
+            cells: archive
+                .cells
+                .iter()
+                .filter(|cell| cell.team.island == island)
+                .cloned()
+                .collect(),
+        };
+        let offset = next_pop.len();
+        let members = next_generation(
+            &mut island_scored,
+            &island_config,
+            generation,
+            knowledge,
+            &island_archive,
+            rng,
+        );
+        for (i, mut member) in members.into_iter().enumerate() {
+            member.team.name = team_name(offset + i, generation);
+            member.team.island = island;
+            next_pop.push(member);
+        }
+    }
+    (next_pop, migrations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(quality: f64, consistency: f64, efficiency: f64) -> FitnessScore {
+        FitnessScore {
+            quality,
+            consistency,
+            efficiency,
+            diversity_penalty: 0.0,
+            total: (quality + consistency + efficiency) / 3.0,
+            judge_critique: String::new(),
+            critiques: Vec::new(),
+        }
+    }
+
+    fn scored_team(name: &str, strategies: &[ReasoningStrategy], total: f64) -> ScoredTeam {
+        let team = Team {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            agents: strategies
+                .iter()
+                .map(|strategy| Agent::new(Genotype::new(name, strategy.clone(), 0.7)))
+                .collect(),
+            generation: 0,
+            island: 0,
+        };
+        let output = TeamOutput {
+            team_id: team.id,
+            team_name: team.name.clone(),
+            agent_outputs: Vec::new(),
+            total_tokens: 0,
+            total_cost: 0.0,
+            total_elapsed: std::time::Duration::ZERO,
+            warnings: Vec::new(),
+        };
+        ScoredTeam {
+            team,
+            output,
+            score: score(total, total, total),
+        }
+    }
+
+    #[test]
+    fn sharing_breeds_a_novel_species_but_keeps_the_best_team() {
+        use ReasoningStrategy::*;
+        let population = || {
+            vec![
+                scored_team("Clone-1", &[Logical, Critical], 8.0),
+                scored_team("Clone-2", &[Logical, Critical], 7.5),
+                scored_team("Clone-3", &[Critical, Logical], 7.0),
+                scored_team("Novel", &[Creative, DevilsAdvocate], 5.0),
+            ]
+        };
+        let shared = Config {
+            population_size: 4,
+            team_size: 2,
+            elite_count: 1,
+            mutation_rate: 0.0,
+            fitness_sharing: true,
+            archive_parent_rate: 0.0,
+            ..Config::default()
+        };
+        // First-agent names of the elites, then of the children, which
+        // truncation breeds from the top-ranked team alone.
+        let next = |config: &Config| {
+            let mut rng = ChaCha8Rng::seed_from_u64(1);
+            let next = next_generation(
+                &mut population(),
+                config,
+                1,
+                &KnowledgeBase::default(),
+                &EliteArchive::default(),
+                &mut rng,
+            );
+            let names = |elite: bool| {
+                next.iter()
+                    .filter(|m| m.cached.is_some() == elite)
+                    .map(|m| m.team.agents[0].genotype.name.clone())
+                    .collect::<Vec<_>>()
+            };
+            (names(true), names(false))
+        };
+
+        // Three near-clones split 22.5 between them; the novel team keeps 5
+        // and breeds, while the best team by raw score stays an elite.
+        let (elites, children) = next(&shared);
+        assert_eq!(elites, vec!["Clone-1"]);
+        assert_eq!(children, vec!["Novel"; 3]);
+
+        let unshared = Config {
+            fitness_sharing: false,
+            ..shared
+        };
+        let (elites, children) = next(&unshared);
+        assert_eq!(elites, vec!["Clone-1"]);
+        assert_eq!(children, vec!["Clone-1"; 3]);
+    }
+
+    #[test]
+    fn archive_elites_are_drawn_as_parents() {
+        use ReasoningStrategy::*;
+        let mut archive = EliteArchive::default();
+        archive.insert(&[scored_team("Archived", &[Creative, Creative], 9.0)]);
+        let config = Config {
+            population_size: 20,
+            team_size: 2,
+            elite_count: 1,
+            mutation_rate: 0.0,
+            archive_parent_rate: 0.2,
+            ..Config::default()
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let next = next_generation(
+            &mut vec![
+                scored_team("Current-1", &[Logical, Logical], 6.0),
+                scored_team("Current-2", &[Critical, Critical], 5.0),
+            ],
+            &config,
+            1,
+            &KnowledgeBase::default(),
+            &archive,
+            &mut rng,
+        );
+
+        // A child's first agent is copied from one of its two parents.
+        let from_archive = next
+            .iter()
+            .filter(|m| m.cached.is_none())
+            .filter(|m| m.team.agents[0].genotype.name == "Archived")
+            .count();
+        assert!(from_archive > 0 && from_archive < next.len() - 1);
+    }
+
+    #[test]
+    fn islands_only_breed_from_their_own_archive_cells() {
+        use ReasoningStrategy::*;
+        let on_island = |mut st: ScoredTeam, island: usize| {
+            st.team.island = island;
+            st
+        };
+        let mut archive = EliteArchive::default();
+        archive.insert(&[on_island(scored_team("Archived", &[Creative, Creative], 9.0), 1)]);
+        let config = Config {
+            population_size: 4,
+            team_size: 2,
+            islands: 2,
+            mutation_rate: 0.0,
+            archive_parent_rate: 1.0,
+            ..Config::default()
+        };
+        let mut scored = vec![
+            on_island(scored_team("Home-1", &[Logical, Logical], 6.0), 0),
+            on_island(scored_team("Home-2", &[Critical, Critical], 5.0), 0),
+            on_island(scored_team("Away-1", &[Logical, Logical], 6.0), 1),
+            on_island(scored_team("Away-2", &[Critical, Critical], 5.0), 1),
+        ];
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let (next, migrations) = next_generation_islands(
+            &mut scored,
+            &config,
+            1,
+            &KnowledgeBase::default(),
+            &archive,
+            &mut rng,
+        );
+
+        assert!(migrations.is_empty());
+        let children: Vec<(usize, &str)> = next
+            .iter()
+            .filter(|m| m.cached.is_none())
+            .map(|m| (m.team.island, m.team.agents[0].genotype.name.as_str()))
+            .collect();
+        assert_eq!(children.len(), 2);
+        for (island, name) in children {
+            assert_eq!(name == "Archived", island == 1);
+        }
+    }
+
+    #[test]
+    fn trade_offs_share_the_first_front_and_extremes_are_most_crowded_out() {
+        let scores = [
+            score(9.0, 5.0, 5.0),
+            score(5.0, 9.0, 5.0),
+            score(5.0, 5.0, 9.0),
+            score(4.0, 4.0, 4.0),
+            score(6.0, 6.0, 6.0),
+        ];
+        let refs: Vec<&FitnessScore> = scores.iter().collect();
+
+        let fronts = non_dominated_fronts(&refs);
+        assert_eq!(fronts, vec![vec![0, 1, 2, 4], vec![3]]);
+
+        // Each specialist is an extreme on some objective; the all-rounder is not.
+        let distances = crowding_distances(&fronts[0], &refs);
+        assert!(distances[..3].iter().all(|d| d.is_infinite()));
+        assert!(distances[3].is_finite() && distances[3] > 0.0);
+
+        assert!(dominates(&scores[4], &scores[3]));
+        assert!(!dominates(&scores[0], &scores[4]));
+        assert!(!dominates(&scores[4], &scores[4]));
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::arena::EvolutionResult;
+use crate::config::Config;
+
+/// A finished run as kept by [`RunStore`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: String,
+    /// Unix seconds when the run was saved.
+    pub created_at: u64,
+    pub problem: String,
+    pub config: Config,
+    pub result: EvolutionResult,
+}
+
+impl RunRecord {
+    /// Age relative to now, e.g. `"5m ago"` or `"3d ago"`.
+    pub fn age(&self) -> String {
+        let secs = unix_now().saturating_sub(self.created_at);
+        match secs {
+            0..=59 => "just now".into(),
+            60..=3599 => format!("{}m ago", secs / 60),
+            3600..=86_399 => format!("{}h ago", secs / 3600),
+            _ => format!("{}d ago", secs / 86_400),
+        }
+    }
+}
+
+/// One JSON file per run under `<data dir>/emas/runs`.
+pub struct RunStore {
+    dir: PathBuf,
+}
+
+impl RunStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn open_default() -> Result<Self> {
+        let dir = dirs::data_local_dir()
+            .ok_or_else(|| anyhow!("No local data directory on this platform"))?;
+        Ok(Self::new(dir.join("emas").join("runs")))
+    }
+
+    /// Stores a finished run and returns its record.
+    pub fn save(
+        &self,
+        problem: &str,
+        config: &Config,
+        result: &EvolutionResult,
+    ) -> Result<RunRecord> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        let record = RunRecord {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            created_at: unix_now(),
+            problem: problem.to_string(),
+            config: config.clone(),
+            result: result.clone(),
+        };
+        let path = self.dir.join(format!("{}.json", record.id));
+        fs::write(&path, serde_json::to_string(&record)?)
+            .with_context(|| format!("writing {}", path.display()))?;
+        Ok(record)
+    }
+
+    /// Every stored run, newest first. Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<RunRecord>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", self.dir.display())),
+        };
+        let mut records = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            match fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|raw| Ok(serde_json::from_str::<RunRecord>(&raw)?))
+            {
+                Ok(record) => records.push(record),
+                Err(e) => warn!(path = %path.display(), "Skipping unreadable run: {e:#}"),
+            }
+        }
+        records.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        Ok(records)
+    }
+
+    /// Loads a run by id or unique id prefix.
+    pub fn load(&self, id: &str) -> Result<RunRecord> {
+        let path = self.resolve(id)?;
+        let raw =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Deletes a run by id or unique id prefix and returns its full id.
+    pub fn remove(&self, id: &str) -> Result<String> {
+        let path = self.resolve(id)?;
+        fs::remove_file(&path).with_context(|| format!("removing {}", path.display()))?;
+        Ok(path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default())
+    }
+
+    fn resolve(&self, id: &str) -> Result<PathBuf> {
+        if id.is_empty() {
+            bail!("Run id cannot be empty");
+        }
+        let matches: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| {
+                        p.extension().is_some_and(|ext| ext == "json")
+                            && p.file_stem()
+                                .is_some_and(|s| s.to_string_lossy().starts_with(id))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        match matches.len() {
+            0 => bail!("No run with id '{id}' (see `emas history list`)"),
+            1 => Ok(matches.into_iter().next().expect("one match")),
+            n => bail!("Run id '{id}' is ambiguous ({n} matches)"),
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::config::Provider;
+
+    #[tokio::test]
+    async fn saved_runs_can_be_listed_loaded_by_prefix_and_removed() {
+        let config = Config {
+            provider: Provider::Mock,
+            model: "mock".into(),
+            judge_provider: Provider::Mock,
+            judge_model: "mock".into(),
+            population_size: 3,
+            max_generations: 2,
+            fitness_threshold: 10.0,
+            ..Config::default()
+        };
+        let arena = Arena::new(config).unwrap();
+        let result = arena.run("Is 7919 prime?").await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("emas-runs-{}", uuid::Uuid::new_v4()));
+        let store = RunStore::new(&dir);
+        let saved = store
+            .save("Is 7919 prime?", &arena.config, &result)
+            .unwrap();
+
+        let listed = store.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, saved.id);
+
+        let loaded = store.load(&saved.id[..4]).unwrap();
+        assert_eq!(loaded.problem, "Is 7919 prime?");
+        assert_eq!(loaded.result.generation_scores.len(), 2);
+        assert_eq!(loaded.result.synthesis, result.synthesis);
+
+        assert_eq!(store.remove(&saved.id).unwrap(), saved.id);
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.load(&saved.id).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
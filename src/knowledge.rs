@@ -1,9 +1,11 @@
 use std::collections::HashSet;
 
+use serde::{Deserialize, Serialize};
+
 use crate::llm::LlmClient;
 use crate::team::TeamOutput;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct KnowledgeBase {
     insights: Vec<String>,
     seen: HashSet<String>,
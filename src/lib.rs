@@ -1,10 +1,20 @@
 pub mod agent;
+pub mod archive;
 pub mod arena;
+pub mod bench;
+pub mod checkpoint;
 pub mod config;
+pub mod config_file;
 pub mod evolution;
 pub mod genotype;
+pub mod history;
 pub mod knowledge;
 pub mod llm;
 pub mod orchestrator;
+pub mod problem;
+pub mod report;
+pub mod selection;
+pub mod server;
+pub mod species;
 pub mod team;
 pub mod tui;
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::{ApiError, BackendCapabilities, BackendSpec, ChatRequest, LlmBackend, LlmResponse};
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Anthropic Messages API backend (`POST /v1/messages`).
+pub struct AnthropicBackend {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    system: &'a str,
+    messages: Vec<Message<'a>>,
+    temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+struct MessagesResponseBody {
+    content: Vec<ContentBlock>,
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Usage {
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
+}
+
+impl AnthropicBackend {
+    pub fn new(spec: &BackendSpec) -> Result<Self> {
+        Ok(Self {
+            client: super::http_client()?,
+            base_url: spec.base_url.trim_end_matches('/').to_string(),
+            api_key: spec.api_key.clone(),
+            model: spec.model.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl LlmBackend for AnthropicBackend {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            system_prompt: true,
+            top_p: true,
+            usage_reporting: true,
+            streaming: false,
+        }
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<LlmResponse> {
+        let url = format!("{}/messages", self.base_url);
+
+        // The Messages API caps temperature at 1.0 and some models reject
+        // temperature and top_p together, so only send top_p when it matters.
+        let request_body = MessagesRequest {
+            model: &self.model,
+            max_tokens: request.max_tokens,
+            system: request.system_prompt,
+            messages: vec![Message {
+                role: "user",
+                content: request.user_message,
+            }],
+            temperature: request.temperature.clamp(0.0, 1.0),
+            top_p: (request.top_p < 1.0).then_some(request.top_p),
+        };
+
+        debug!(url = %url, model = %self.model, provider = "anthropic", "Sending Messages API request");
+
+        let http_resp = self
+            .client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if !http_resp.status().is_success() {
+            return Err(ApiError::from_response("Anthropic", http_resp).await.into());
+        }
+
+        let body: MessagesResponseBody = http_resp.json().await?;
+
+        let content: String = body
+            .content
+            .iter()
+            .filter(|b| b.kind == "text")
+            .map(|b| b.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (prompt_tokens, completion_tokens, total_tokens) = match body.usage {
+            Some(u) => {
+                let pt = u.input_tokens.unwrap_or(0);
+                let ct = u.output_tokens.unwrap_or(0);
+                (pt, ct, pt + ct)
+            }
+            None => super::estimate_tokens(&content),
+        };
+
+        Ok(LlmResponse {
+            content,
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+        })
+    }
+}
@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use super::{ChatRequest, LlmResponse};
+
+/// Everything that determines a reply, hashed into the cache key.
+#[derive(Serialize)]
+struct CacheKey<'a> {
+    provider: &'a str,
+    model: &'a str,
+    system_prompt: &'a str,
+    user_message: &'a str,
+    temperature: f64,
+    top_p: f64,
+    max_tokens: u32,
+    seed: Option<u64>,
+}
+
+/// Content-addressed on-disk store of LLM responses, one JSON file per
+/// request hash. Shared by the agent and judge clients so re-judging an
+/// equivalent team or re-running generation 0 of the same problem is free.
+///
+/// The cache is best-effort: unreadable entries count as misses and write
+/// failures are logged, never surfaced to the run.
+pub struct ResponseCache {
+    dir: PathBuf,
+    deterministic_only: bool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            deterministic_only: false,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Skips calls with `temperature > 0`, whose replies are meant to vary.
+    pub fn deterministic_only(mut self, yes: bool) -> Self {
+        self.deterministic_only = yes;
+        self
+    }
+
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|d| d.join("emas").join("responses"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `(hits, misses)` since this cache was opened.
+    pub fn stats(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    fn path(&self, provider: &str, model: &str, request: &ChatRequest<'_>) -> Option<PathBuf> {
+        if self.deterministic_only && request.temperature > 0.0 {
+            return None;
+        }
+        let key = CacheKey {
+            provider,
+            model,
+            system_prompt: request.system_prompt,
+            user_message: request.user_message,
+            temperature: request.temperature,
+            top_p: request.top_p,
+            max_tokens: request.max_tokens,
+            seed: request.seed,
+        };
+        let json = serde_json::to_vec(&key).expect("cache key serialises to JSON");
+        let hash = format!("{:x}", Sha256::digest(json));
+        Some(self.dir.join(&hash[..2]).join(format!("{hash}.json")))
+    }
+
+    pub(crate) fn get(
+        &self,
+        provider: &str,
+        model: &str,
+        request: &ChatRequest<'_>,
+    ) -> Option<LlmResponse> {
+        let path = self.path(provider, model, request)?;
+        let cached = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok());
+        let counter = if cached.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    pub(crate) fn put(
+        &self,
+        provider: &str,
+        model: &str,
+        request: &ChatRequest<'_>,
+        response: &LlmResponse,
+    ) {
+        let Some(path) = self.path(provider, model, request) else {
+            return;
+        };
+        let write = || -> anyhow::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+            fs::write(&tmp, serde_json::to_vec(response)?)?;
+            fs::rename(&tmp, &path)?;
+            Ok(())
+        };
+        if let Err(e) = write() {
+            warn!(path = %path.display(), "Could not write response cache entry: {e:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::llm::{LlmClient, MockBackend};
+
+    #[tokio::test]
+    async fn second_identical_call_is_served_from_disk() {
+        let dir = std::env::temp_dir().join(format!("emas-cache-{}", uuid::Uuid::new_v4()));
+        let cache = Arc::new(ResponseCache::new(&dir).deterministic_only(true));
+        let client = LlmClient::from_backend(Arc::new(MockBackend::new("mock")))
+            .with_cache(Some(cache.clone()));
+
+        let ask = |temperature| client.chat_completion("Judge.", "Score this.", temperature, 1.0, 64);
+        let first = ask(0.0).await.unwrap();
+        let second = ask(0.0).await.unwrap();
+        assert_eq!(first.content, second.content);
+        assert_eq!(cache.stats(), (1, 1));
+        assert_eq!(client.tokens_used(), first.total_tokens as u64);
+
+        // Sampled calls bypass a deterministic-only cache entirely.
+        ask(0.7).await.unwrap();
+        ask(0.7).await.unwrap();
+        assert_eq!(cache.stats(), (1, 1));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
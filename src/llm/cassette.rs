@@ -0,0 +1,246 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{ChatRequest, LlmResponse};
+
+/// The parts of a request that identify it in a cassette. The seed is left
+/// out so a recording can be replayed without passing the same `--seed`.
+#[derive(Serialize)]
+struct RecordedRequest<'a> {
+    model: &'a str,
+    system_prompt: &'a str,
+    user_message: &'a str,
+    temperature: f64,
+    top_p: f64,
+    max_tokens: u32,
+}
+
+impl<'a> RecordedRequest<'a> {
+    fn new(model: &'a str, request: &ChatRequest<'a>) -> Self {
+        Self {
+            model,
+            system_prompt: request.system_prompt,
+            user_message: request.user_message,
+            temperature: request.temperature,
+            top_p: request.top_p,
+            max_tokens: request.max_tokens,
+        }
+    }
+
+    fn key(&self) -> String {
+        let json = serde_json::to_vec(self).expect("request serialises to JSON");
+        format!("{:x}", Sha256::digest(json))
+    }
+}
+
+/// One line of a cassette file.
+#[derive(Serialize)]
+struct EntryRef<'a> {
+    key: &'a str,
+    request: &'a RecordedRequest<'a>,
+    response: &'a LlmResponse,
+}
+
+/// What replay needs from a line; the request is only there for humans.
+#[derive(Deserialize)]
+struct Entry {
+    key: String,
+    response: LlmResponse,
+}
+
+/// Calls and tokens recorded for one model, see [`Cassette::summarize`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModelTally {
+    pub calls: usize,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// Just the model of a recorded request, for summaries.
+#[derive(Deserialize)]
+struct RequestModel {
+    model: String,
+}
+
+#[derive(Deserialize)]
+struct SummaryEntry {
+    request: RequestModel,
+    response: LlmResponse,
+}
+
+/// A JSONL log of LLM exchanges shared by the agent and judge clients.
+///
+/// Recording appends every successful request and response, so a resumed
+/// run keeps adding to the same file. Replaying serves responses by request
+/// hash and fails on anything that was not recorded, so scoring and
+/// evolution can be rerun against identical model output.
+/// Identical requests are answered in recorded order, and the last response
+/// is reused once they run out.
+pub enum Cassette {
+    Record(Mutex<File>),
+    Replay(Mutex<HashMap<String, VecDeque<LlmResponse>>>),
+}
+
+impl Cassette {
+    /// Records to `path`, appending if it already exists.
+    pub fn record(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("opening cassette {}", path.display()))?;
+        Ok(Self::Record(Mutex::new(file)))
+    }
+
+    pub fn replay(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("opening cassette {}", path.display()))?;
+        let mut entries: HashMap<String, VecDeque<LlmResponse>> = HashMap::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: Entry = serde_json::from_str(&line)
+                .with_context(|| format!("parsing {} line {}", path.display(), i + 1))?;
+            entries
+                .entry(entry.key)
+                .or_default()
+                .push_back(entry.response);
+        }
+        Ok(Self::Replay(Mutex::new(entries)))
+    }
+
+    /// Per-model call and token counts of a cassette file.
+    pub fn summarize(path: &Path) -> Result<BTreeMap<String, ModelTally>> {
+        let file =
+            File::open(path).with_context(|| format!("opening cassette {}", path.display()))?;
+        let mut tallies: BTreeMap<String, ModelTally> = BTreeMap::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: SummaryEntry = serde_json::from_str(&line)
+                .with_context(|| format!("parsing {} line {}", path.display(), i + 1))?;
+            let tally = tallies.entry(entry.request.model).or_default();
+            tally.calls += 1;
+            tally.prompt_tokens += u64::from(entry.response.prompt_tokens);
+            tally.completion_tokens += u64::from(entry.response.completion_tokens);
+        }
+        Ok(tallies)
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self, Self::Replay(_))
+    }
+
+    pub(crate) fn store(
+        &self,
+        model: &str,
+        request: &ChatRequest<'_>,
+        response: &LlmResponse,
+    ) -> Result<()> {
+        let Self::Record(file) = self else {
+            return Ok(());
+        };
+        let request = RecordedRequest::new(model, request);
+        let key = request.key();
+        let mut line = serde_json::to_string(&EntryRef {
+            key: &key,
+            request: &request,
+            response,
+        })?;
+        line.push('\n');
+        let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(line.as_bytes())
+            .and_then(|()| file.flush())
+            .context("writing to cassette")
+    }
+
+    pub(crate) fn lookup(&self, model: &str, request: &ChatRequest<'_>) -> Result<LlmResponse> {
+        let Self::Replay(entries) = self else {
+            return Err(anyhow!("cassette is recording, not replaying"));
+        };
+        let key = RecordedRequest::new(model, request).key();
+        let mut entries = entries.lock().unwrap_or_else(|e| e.into_inner());
+        let responses = entries.get_mut(&key).ok_or_else(|| {
+            anyhow!(
+                "cassette has no response for this {model} request (key {})",
+                &key[..12]
+            )
+        })?;
+        Ok(if responses.len() > 1 {
+            responses.pop_front().expect("non-empty")
+        } else {
+            responses[0].clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::llm::{LlmClient, MockBackend};
+
+    #[tokio::test]
+    async fn replays_recorded_responses_and_rejects_new_requests() {
+        let path =
+            std::env::temp_dir().join(format!("emas-cassette-{}.jsonl", uuid::Uuid::new_v4()));
+
+        let recorder = LlmClient::from_backend(Arc::new(MockBackend::new("mock")))
+            .with_cassette(Some(Arc::new(Cassette::record(&path).unwrap())));
+        let recorded = recorder
+            .chat_completion(
+                "You are a Logical Reasoner.",
+                "Is 7919 prime?",
+                0.2,
+                1.0,
+                256,
+            )
+            .await
+            .unwrap();
+
+        // The replaying client's own backend would answer differently.
+        let rules = vec![crate::llm::MockRule {
+            response: "live answer".into(),
+            ..Default::default()
+        }];
+        let player = LlmClient::from_backend(Arc::new(MockBackend::with_rules("mock", rules)))
+            .with_cassette(Some(Arc::new(Cassette::replay(&path).unwrap())));
+        let replayed = player
+            .chat_completion(
+                "You are a Logical Reasoner.",
+                "Is 7919 prime?",
+                0.2,
+                1.0,
+                256,
+            )
+            .await
+            .unwrap();
+        assert_eq!(replayed.content, recorded.content);
+        assert_eq!(player.tokens_used(), recorded.total_tokens as u64);
+
+        let miss = player
+            .chat_completion(
+                "You are a Logical Reasoner.",
+                "Is 7919 prime?",
+                0.3,
+                1.0,
+                256,
+            )
+            .await;
+        assert!(miss.unwrap_err().to_string().contains("no response"));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
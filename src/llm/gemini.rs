@@ -0,0 +1,216 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::{
+    estimate_tokens, sse, ApiError, BackendCapabilities, BackendSpec, ChatRequest, DeltaSink,
+    LlmBackend, LlmResponse,
+};
+
+/// Google Gemini `generateContent` backend.
+pub struct GeminiBackend {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GeminiContent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GeminiPart {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    temperature: f64,
+    top_p: f64,
+    max_output_tokens: u32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponseBody {
+    candidates: Option<Vec<GeminiCandidate>>,
+    usage_metadata: Option<GeminiUsageMetadata>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GeminiCandidate {
+    content: Option<GeminiContent>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsageMetadata {
+    prompt_token_count: Option<u32>,
+    candidates_token_count: Option<u32>,
+    total_token_count: Option<u32>,
+}
+
+impl GeminiBackend {
+    pub fn new(spec: &BackendSpec) -> Result<Self> {
+        Ok(Self {
+            client: super::http_client()?,
+            base_url: spec.base_url.trim_end_matches('/').to_string(),
+            api_key: spec.api_key.clone(),
+            model: spec.model.clone(),
+        })
+    }
+
+    async fn send(&self, request: &ChatRequest<'_>, stream: bool) -> Result<reqwest::Response> {
+        let url = if stream {
+            format!(
+                "{}/models/{}:streamGenerateContent?alt=sse&key={}",
+                self.base_url, self.model, self.api_key,
+            )
+        } else {
+            format!(
+                "{}/models/{}:generateContent?key={}",
+                self.base_url, self.model, self.api_key,
+            )
+        };
+
+        let request_body = GeminiRequest {
+            contents: vec![GeminiContent {
+                role: Some("user".into()),
+                parts: vec![GeminiPart {
+                    text: request.user_message.into(),
+                }],
+            }],
+            system_instruction: if request.system_prompt.is_empty() {
+                None
+            } else {
+                Some(GeminiContent {
+                    role: None,
+                    parts: vec![GeminiPart {
+                        text: request.system_prompt.into(),
+                    }],
+                })
+            },
+            generation_config: Some(GeminiGenerationConfig {
+                temperature: request.temperature,
+                top_p: request.top_p,
+                max_output_tokens: request.max_tokens,
+            }),
+        };
+
+        debug!(model = %self.model, provider = "google", stream, "Sending Gemini generateContent request");
+
+        let http_resp = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if !http_resp.status().is_success() {
+            return Err(ApiError::from_response("Google Gemini", http_resp).await.into());
+        }
+        Ok(http_resp)
+    }
+}
+
+#[async_trait]
+impl LlmBackend for GeminiBackend {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            system_prompt: true,
+            top_p: true,
+            usage_reporting: true,
+            streaming: true,
+        }
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<LlmResponse> {
+        let http_resp = self.send(request, false).await?;
+        let body: GeminiResponseBody = http_resp.json().await?;
+
+        let content = body.text().to_string();
+        Ok(into_response(content, body.usage_metadata))
+    }
+
+    async fn chat_stream(
+        &self,
+        request: &ChatRequest<'_>,
+        on_delta: &DeltaSink<'_>,
+    ) -> Result<LlmResponse> {
+        let http_resp = self.send(request, true).await?;
+
+        let mut content = String::new();
+        let mut usage = None;
+        sse::for_each_event(http_resp, |data| {
+            let chunk: GeminiResponseBody = serde_json::from_str(data)?;
+            let delta = chunk.text();
+            if !delta.is_empty() {
+                on_delta(delta);
+                content.push_str(delta);
+            }
+            if chunk.usage_metadata.is_some() {
+                usage = chunk.usage_metadata;
+            }
+            Ok(())
+        })
+        .await?;
+
+        Ok(into_response(content, usage))
+    }
+}
+
+impl GeminiResponseBody {
+    fn text(&self) -> &str {
+        self.candidates
+            .as_ref()
+            .and_then(|c| c.first())
+            .and_then(|c| c.content.as_ref())
+            .and_then(|c| c.parts.first())
+            .map(|p| p.text.as_str())
+            .unwrap_or_default()
+    }
+}
+
+fn into_response(content: String, usage: Option<GeminiUsageMetadata>) -> LlmResponse {
+    let (prompt_tokens, completion_tokens, total_tokens) = match usage {
+        Some(u) => {
+            let pt = u.prompt_token_count.unwrap_or(0);
+            let ct = u.candidates_token_count.unwrap_or(0);
+            let tt = u.total_token_count.unwrap_or(pt + ct);
+            (pt, ct, tt)
+        }
+        None => estimate_tokens(&content),
+    };
+
+    LlmResponse {
+        content,
+        prompt_tokens,
+        completion_tokens,
+        total_tokens,
+    }
+}
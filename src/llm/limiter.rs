@@ -0,0 +1,158 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// Request-concurrency cap plus requests-per-minute and tokens-per-minute
+/// budgets. One limiter is shared by every client of a run so agents and
+/// judges draw from the same quota.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    concurrency: Option<Arc<Semaphore>>,
+    requests: Option<Mutex<TokenBucket>>,
+    tokens: Option<Mutex<TokenBucket>>,
+}
+
+/// Held for the duration of one request. Call [`RatePermit::settle`] with the
+/// real usage so the TPM budget reflects what the provider actually charged.
+/// A permit dropped unsettled, e.g. by a cancelled request, refunds its
+/// whole reservation.
+pub struct RatePermit<'a> {
+    limiter: &'a RateLimiter,
+    reserved_tokens: f64,
+    used_tokens: f64,
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+impl RateLimiter {
+    pub fn new(max_concurrency: Option<usize>, rpm: Option<u32>, tpm: Option<u32>) -> Self {
+        Self {
+            concurrency: max_concurrency
+                .filter(|n| *n > 0)
+                .map(|n| Arc::new(Semaphore::new(n))),
+            requests: rpm.filter(|n| *n > 0).map(|n| Mutex::new(TokenBucket::per_minute(n))),
+            tokens: tpm.filter(|n| *n > 0).map(|n| Mutex::new(TokenBucket::per_minute(n))),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.concurrency.is_none() && self.requests.is_none() && self.tokens.is_none()
+    }
+
+    /// Waits until a request estimated at `estimated_tokens` fits every budget.
+    pub async fn acquire(&self, estimated_tokens: u32) -> RatePermit<'_> {
+        let slot = match &self.concurrency {
+            Some(sem) => sem.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        if let Some(bucket) = &self.requests {
+            TokenBucket::take(bucket, 1.0).await;
+        }
+        let mut reserved_tokens = 0.0;
+        if let Some(bucket) = &self.tokens {
+            reserved_tokens = TokenBucket::take(bucket, estimated_tokens as f64).await;
+        }
+        RatePermit {
+            limiter: self,
+            reserved_tokens,
+            used_tokens: 0.0,
+            _slot: slot,
+        }
+    }
+}
+
+impl RatePermit<'_> {
+    pub fn settle(mut self, actual_tokens: u32) {
+        self.used_tokens = actual_tokens as f64;
+    }
+}
+
+impl Drop for RatePermit<'_> {
+    fn drop(&mut self) {
+        if let Some(bucket) = &self.limiter.tokens {
+            bucket
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .adjust(self.used_tokens - self.reserved_tokens);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    available: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn per_minute(limit: u32) -> Self {
+        let capacity = limit as f64;
+        Self {
+            capacity,
+            available: capacity,
+            refill_per_sec: capacity / 60.0,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Debits `amount` (clamped to the bucket size so oversized requests
+    /// cannot wait forever), sleeping until enough budget has refilled.
+    /// Returns the amount actually reserved.
+    async fn take(bucket: &Mutex<Self>, amount: f64) -> f64 {
+        loop {
+            let wait = {
+                let mut b = bucket.lock().unwrap_or_else(|e| e.into_inner());
+                b.refill();
+                let amount = amount.min(b.capacity);
+                if b.available >= amount {
+                    b.available -= amount;
+                    return amount;
+                }
+                (amount - b.available) / b.refill_per_sec
+            };
+            tokio::time::sleep(Duration::from_secs_f64(wait.max(0.01))).await;
+        }
+    }
+
+    /// Applies the difference between estimated and actual usage. The balance
+    /// may go negative, which simply delays later requests.
+    fn adjust(&mut self, delta: f64) {
+        self.refill();
+        self.available = (self.available - delta).min(self.capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rpm_budget_delays_excess_requests() {
+        let limiter = RateLimiter::new(None, Some(600), None);
+        let start = Instant::now();
+        for _ in 0..601 {
+            limiter.acquire(0).await.settle(0);
+        }
+        // 600 requests fit the initial bucket; the next waits for one refill (100ms).
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn dropped_permit_refunds_its_token_reservation() {
+        let limiter = RateLimiter::new(None, None, Some(600));
+        drop(limiter.acquire(600).await);
+        // Without the refund the second request would wait a full minute.
+        let again = tokio::time::timeout(Duration::from_millis(100), limiter.acquire(600)).await;
+        assert!(again.is_ok());
+    }
+}
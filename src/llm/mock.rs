@@ -0,0 +1,225 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    BackendCapabilities, BackendSpec, ChatRequest, DeltaSink, LlmBackend, LlmResponse,
+};
+use crate::genotype::ReasoningStrategy;
+
+/// A scripted reply: returned when every non-empty matcher is a substring of
+/// the corresponding prompt. Rules are tried in order before the built-in ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockRule {
+    pub system_contains: String,
+    pub user_contains: String,
+    pub response: String,
+}
+
+impl MockRule {
+    fn matches(&self, request: &ChatRequest<'_>) -> bool {
+        request.system_prompt.contains(&self.system_contains)
+            && request.user_message.contains(&self.user_contains)
+    }
+}
+
+/// Offline, deterministic backend for tests and demos.
+///
+/// Without a script it answers by rule: agents get a canned answer keyed by
+/// the strategy in their system prompt, judges get a JSON verdict whose score
+/// grows with the number of distinct strategies on the team, and the knowledge
+/// curator and synthesiser get well-formed replies. The same request always
+/// produces the same response.
+pub struct MockBackend {
+    model: String,
+    rules: Vec<MockRule>,
+}
+
+impl MockBackend {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            rules: Vec::new(),
+        }
+    }
+
+    pub fn with_rules(model: &str, rules: Vec<MockRule>) -> Self {
+        Self {
+            model: model.to_string(),
+            rules,
+        }
+    }
+
+    /// Loads scripted rules when the spec's base URL points at a JSON file
+    /// (`[{"system_contains": ..., "user_contains": ..., "response": ...}]`).
+    /// A bare `mock://` or an HTTP endpoint (e.g. another provider's default)
+    /// uses only the built-in rules; a missing file is an error rather than a
+    /// silent fallback.
+    pub fn from_spec(spec: &BackendSpec) -> Result<Self> {
+        let path = spec.base_url.trim_start_matches("mock://");
+        if path.is_empty() || path.starts_with("http://") || path.starts_with("https://") {
+            return Ok(Self::new(&spec.model));
+        }
+        if !Path::new(path).is_file() {
+            bail!("mock script {path} not found");
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("reading mock script {path}"))?;
+        let rules: Vec<MockRule> = serde_json::from_str(&raw)
+            .with_context(|| format!("parsing mock script {path}"))?;
+        Ok(Self::with_rules(&spec.model, rules))
+    }
+
+    fn respond(&self, request: &ChatRequest<'_>) -> String {
+        if let Some(rule) = self.rules.iter().find(|r| r.matches(request)) {
+            return rule.response.clone();
+        }
+
+        let system = request.system_prompt;
+        if system.contains("evaluation judge") {
+            judge_reply(request.user_message)
+        } else if system.contains("knowledge curator") {
+            "[\"Check every constraint against the proposed answer.\", \
+              \"Consider whether a second valid solution exists.\"]"
+                .to_string()
+        } else if system.contains("synthesis expert") {
+            format!(
+                "Synthesised answer (mock).\n\n{}",
+                first_line_after(request.user_message, "**Problem:**")
+            )
+        } else {
+            agent_reply(system, request.user_message)
+        }
+    }
+}
+
+#[async_trait]
+impl LlmBackend for MockBackend {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            system_prompt: true,
+            top_p: true,
+            usage_reporting: true,
+            streaming: true,
+        }
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<LlmResponse> {
+        let content = self.respond(request);
+
+        let prompt_tokens =
+            ((request.system_prompt.len() + request.user_message.len()) / 4) as u32;
+        let completion_tokens = ((content.len() / 4) as u32).min(request.max_tokens);
+
+        Ok(LlmResponse {
+            content,
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+        })
+    }
+
+    /// Emits the canned reply one line at a time.
+    async fn chat_stream(
+        &self,
+        request: &ChatRequest<'_>,
+        on_delta: &DeltaSink<'_>,
+    ) -> Result<LlmResponse> {
+        let response = self.chat(request).await?;
+        for line in response.content.split_inclusive('\n') {
+            on_delta(line);
+            tokio::task::yield_now().await;
+        }
+        Ok(response)
+    }
+}
+
+fn agent_reply(system: &str, problem: &str) -> String {
+    let strategy = ReasoningStrategy::all()
+        .iter()
+        .find(|s| system.contains(&format!("Reasoning Strategy: {s}")))
+        .cloned()
+        .unwrap_or(ReasoningStrategy::ChainOfThought);
+
+    let topic = problem.lines().next().unwrap_or_default();
+    let body = match strategy {
+        ReasoningStrategy::ChainOfThought => {
+            "1. Restate the problem.\n2. Break it into sub-problems.\n3. Solve each in turn."
+        }
+        ReasoningStrategy::Critical => "The obvious reading hides an assumption worth testing.",
+        ReasoningStrategy::Creative => "An analogy from another domain suggests a different framing.",
+        ReasoningStrategy::Logical => "From the stated premises the conclusion follows deductively.",
+        ReasoningStrategy::EdgeCaseAnalysis => "Empty, maximal and duplicate inputs all need handling.",
+        ReasoningStrategy::PerformanceFocus => "The dominant cost is linear in the input size.",
+        ReasoningStrategy::DevilsAdvocate => "The popular answer fails under one realistic scenario.",
+        ReasoningStrategy::FirstPrinciples => "Reducing to fundamentals leaves two essential constraints.",
+        ReasoningStrategy::RedTeam => "No second valid solution survives the constraint check.",
+    };
+
+    format!(
+        "## Analysis ({strategy})\n{body}\n\nTopic: {topic}\n\n\
+         ## Verification\nEvery constraint holds; the counter-hypothesis violates the first one.\n\n\
+         Conclusion: the answer follows from a {strategy} reading of the problem."
+    )
+}
+
+fn judge_reply(user_message: &str) -> String {
+    let distinct = ReasoningStrategy::all()
+        .iter()
+        .filter(|s| user_message.contains(&format!("({s})")))
+        .count();
+    let jitter = (fnv1a(user_message) % 10) as f64 / 10.0;
+    let score = (4.0 + 1.5 * distinct as f64 + jitter).clamp(1.0, 10.0);
+
+    serde_json::json!({
+        "score": score,
+        "reasoning": format!(
+            "Mock verdict: {distinct} distinct strategies contributed. \
+             The agents failed to consider an alternative interpretation."
+        ),
+    })
+    .to_string()
+}
+
+fn first_line_after<'a>(text: &'a str, marker: &str) -> &'a str {
+    text.split_once(marker)
+        .map(|(_, rest)| rest.trim_start().lines().next().unwrap_or_default())
+        .unwrap_or_default()
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_script_is_an_error() {
+        let spec = |base_url: &str| BackendSpec {
+            base_url: base_url.into(),
+            api_key: String::new(),
+            model: "mock".into(),
+        };
+        assert!(MockBackend::from_spec(&spec("mock://")).is_ok());
+        assert!(MockBackend::from_spec(&spec("https://api.openai.com/v1")).is_ok());
+        let Err(err) = MockBackend::from_spec(&spec("mock://no-such-script.json")) else {
+            panic!("a missing script should not fall back to the built-in rules");
+        };
+        assert!(err.to_string().contains("not found"));
+    }
+}
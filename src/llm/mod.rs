@@ -0,0 +1,450 @@
+mod anthropic;
+mod cache;
+mod cassette;
+mod gemini;
+mod limiter;
+mod mock;
+mod openai;
+mod pricing;
+mod retry;
+mod sse;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::Provider;
+
+pub use anthropic::AnthropicBackend;
+pub use cache::ResponseCache;
+pub use cassette::{Cassette, ModelTally};
+pub use gemini::GeminiBackend;
+pub use limiter::RateLimiter;
+pub use mock::{MockBackend, MockRule};
+pub use openai::OpenAiBackend;
+pub use pricing::{ModelPrice, PricingTable};
+pub use retry::{parse_retry_after, ApiError, RetryPolicy};
+
+/// A single chat turn sent to a backend.
+#[derive(Debug, Clone, Copy)]
+pub struct ChatRequest<'a> {
+    pub system_prompt: &'a str,
+    pub user_message: &'a str,
+    pub temperature: f64,
+    pub top_p: f64,
+    pub max_tokens: u32,
+    /// Sampling seed; honoured by OpenAI-compatible backends, ignored elsewhere.
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmResponse {
+    pub content: String,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// What a backend natively supports. Callers may use this to adapt requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackendCapabilities {
+    /// Accepts a dedicated system prompt (otherwise it is prepended to the user turn).
+    pub system_prompt: bool,
+    /// Honours nucleus sampling (`top_p`).
+    pub top_p: bool,
+    /// Reports real token usage; if false, counts are estimated from the content.
+    pub usage_reporting: bool,
+    /// Delivers the reply incrementally through [`LlmBackend::chat_stream`].
+    pub streaming: bool,
+}
+
+/// Receives each text fragment of a streamed reply as it arrives.
+pub type DeltaSink<'a> = dyn Fn(&str) + Send + Sync + 'a;
+
+/// A chat-completion provider. Implement this to plug a new model API into EMAS.
+#[async_trait]
+pub trait LlmBackend: Send + Sync {
+    /// Short identifier used in logs and the backend registry.
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+
+    fn capabilities(&self) -> BackendCapabilities;
+
+    /// Sends one system + user turn and returns the reply with its token usage.
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<LlmResponse>;
+
+    /// Like [`chat`](Self::chat) but forwards text to `on_delta` as it is
+    /// generated. Backends without streaming emit the whole reply at once.
+    async fn chat_stream(
+        &self,
+        request: &ChatRequest<'_>,
+        on_delta: &DeltaSink<'_>,
+    ) -> Result<LlmResponse> {
+        let response = self.chat(request).await?;
+        on_delta(&response.content);
+        Ok(response)
+    }
+}
+
+/// Connection settings handed to a backend factory.
+#[derive(Debug, Clone)]
+pub struct BackendSpec {
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+}
+
+pub type BackendFactory =
+    Arc<dyn Fn(&BackendSpec) -> Result<Arc<dyn LlmBackend>> + Send + Sync>;
+
+fn registry() -> &'static RwLock<HashMap<String, BackendFactory>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, BackendFactory>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut map: HashMap<String, BackendFactory> = HashMap::new();
+        map.insert(
+            Provider::Openai.key().into(),
+            Arc::new(|spec| Ok(Arc::new(OpenAiBackend::new(spec)?) as Arc<dyn LlmBackend>)),
+        );
+        map.insert(
+            Provider::Google.key().into(),
+            Arc::new(|spec| Ok(Arc::new(GeminiBackend::new(spec)?) as Arc<dyn LlmBackend>)),
+        );
+        map.insert(
+            Provider::Anthropic.key().into(),
+            Arc::new(|spec| Ok(Arc::new(AnthropicBackend::new(spec)?) as Arc<dyn LlmBackend>)),
+        );
+        map.insert(
+            Provider::Mock.key().into(),
+            Arc::new(|spec| Ok(Arc::new(MockBackend::from_spec(spec)?) as Arc<dyn LlmBackend>)),
+        );
+        RwLock::new(map)
+    })
+}
+
+/// Registers (or replaces) a backend factory under `name`.
+///
+/// Built-in providers are registered under their [`Provider::key`], so a
+/// downstream crate can also override how e.g. `"openai"` is constructed.
+pub fn register_backend<F>(name: &str, factory: F)
+where
+    F: Fn(&BackendSpec) -> Result<Arc<dyn LlmBackend>> + Send + Sync + 'static,
+{
+    registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.to_lowercase(), Arc::new(factory));
+}
+
+/// Builds a backend from the registry.
+pub fn create_backend(name: &str, spec: &BackendSpec) -> Result<Arc<dyn LlmBackend>> {
+    let factory = registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&name.to_lowercase())
+        .cloned()
+        .ok_or_else(|| anyhow!("No LLM backend registered under '{name}'"))?;
+    factory(spec)
+}
+
+pub fn registered_backends() -> Vec<String> {
+    let mut names: Vec<String> = registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+pub struct LlmClient {
+    backend: Arc<dyn LlmBackend>,
+    retry: RetryPolicy,
+    retry_log: Mutex<Vec<String>>,
+    limiter: Option<Arc<RateLimiter>>,
+    price: Option<ModelPrice>,
+    seed: Option<u64>,
+    cassette: Option<Arc<Cassette>>,
+    cache: Option<Arc<ResponseCache>>,
+    usage: Mutex<(u64, f64)>,
+}
+
+impl LlmClient {
+    pub fn new(base_url: &str, api_key: &str, model: &str, provider: Provider) -> Result<Self> {
+        let spec = BackendSpec {
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        };
+        Ok(Self::from_backend(create_backend(provider.key(), &spec)?))
+    }
+
+    pub fn from_backend(backend: Arc<dyn LlmBackend>) -> Self {
+        Self {
+            backend,
+            retry: RetryPolicy::default(),
+            retry_log: Mutex::new(Vec::new()),
+            limiter: None,
+            price: None,
+            seed: None,
+            cassette: None,
+            cache: None,
+            usage: Mutex::new((0, 0.0)),
+        }
+    }
+
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Shares a concurrency / RPM / TPM budget with other clients.
+    pub fn with_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = (!limiter.is_unlimited()).then_some(limiter);
+        self
+    }
+
+    /// Prices this client's calls; `None` leaves cost unknown (reported as $0).
+    pub fn with_price(mut self, price: Option<ModelPrice>) -> Self {
+        self.price = price;
+        self
+    }
+
+    /// Sends `seed` with every request to backends that support one.
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Records every exchange to, or serves every reply from, `cassette`.
+    /// May be shared with other clients.
+    pub fn with_cassette(mut self, cassette: Option<Arc<Cassette>>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+
+    /// Serves repeated requests from `cache` instead of the provider. Cache
+    /// hits are free: they add nothing to [`spent`](Self::spent) or
+    /// [`tokens_used`](Self::tokens_used).
+    pub fn with_cache(mut self, cache: Option<Arc<ResponseCache>>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn cache(&self) -> Option<&Arc<ResponseCache>> {
+        self.cache.as_ref()
+    }
+
+    pub fn price(&self) -> Option<ModelPrice> {
+        self.price
+    }
+
+    /// USD cost of one response at this client's price.
+    pub fn cost_of(&self, response: &LlmResponse) -> f64 {
+        self.price
+            .map(|p| p.cost(response.prompt_tokens, response.completion_tokens))
+            .unwrap_or(0.0)
+    }
+
+    /// Total USD spent through this client so far.
+    pub fn spent(&self) -> f64 {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner()).1
+    }
+
+    /// Total tokens (prompt + completion) used through this client so far.
+    pub fn tokens_used(&self) -> u64 {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner()).0
+    }
+
+    /// Drains the retries performed since the last call into a one-line
+    /// summary, e.g. `"3 retries (HTTP 429 x2, timeout x1)"`.
+    pub fn take_retry_summary(&self) -> Option<String> {
+        let reasons = std::mem::take(&mut *self.retry_log.lock().unwrap_or_else(|e| e.into_inner()));
+        if reasons.is_empty() {
+            return None;
+        }
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for reason in &reasons {
+            match counts.iter_mut().find(|(r, _)| r == reason) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((reason.clone(), 1)),
+            }
+        }
+        let breakdown: Vec<String> = counts.iter().map(|(r, n)| format!("{r} x{n}")).collect();
+        Some(format!(
+            "{} {} ({})",
+            reasons.len(),
+            if reasons.len() == 1 { "retry" } else { "retries" },
+            breakdown.join(", "),
+        ))
+    }
+
+    pub fn backend(&self) -> &Arc<dyn LlmBackend> {
+        &self.backend
+    }
+
+    pub async fn chat_completion(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        temperature: f64,
+        top_p: f64,
+        max_tokens: u32,
+    ) -> Result<LlmResponse> {
+        self.complete(system_prompt, user_message, temperature, top_p, max_tokens, None)
+            .await
+    }
+
+    /// Streams the reply into `on_delta` while it is generated. A retried
+    /// request starts over, so sinks may see a partial reply followed by a
+    /// fresh one.
+    pub async fn chat_completion_streaming(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        temperature: f64,
+        top_p: f64,
+        max_tokens: u32,
+        on_delta: &DeltaSink<'_>,
+    ) -> Result<LlmResponse> {
+        self.complete(
+            system_prompt,
+            user_message,
+            temperature,
+            top_p,
+            max_tokens,
+            Some(on_delta),
+        )
+        .await
+    }
+
+    fn add_usage(&self, resp: &LlmResponse) {
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        usage.0 += resp.total_tokens as u64;
+        usage.1 += self.cost_of(resp);
+    }
+
+    async fn complete(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        temperature: f64,
+        top_p: f64,
+        max_tokens: u32,
+        on_delta: Option<&DeltaSink<'_>>,
+    ) -> Result<LlmResponse> {
+        let original = ChatRequest {
+            system_prompt,
+            user_message,
+            temperature,
+            top_p,
+            max_tokens,
+            seed: self.seed,
+        };
+        if let Some(cassette) = self.cassette.as_ref().filter(|c| c.is_replay()) {
+            let resp = cassette.lookup(self.backend.model(), &original)?;
+            if let Some(sink) = on_delta {
+                sink(&resp.content);
+            }
+            self.add_usage(&resp);
+            return Ok(resp);
+        }
+        let (provider, model) = (self.backend.name(), self.backend.model());
+        if let Some(resp) = self.cache.as_ref().and_then(|c| c.get(provider, model, &original)) {
+            if let Some(sink) = on_delta {
+                sink(&resp.content);
+            }
+            // Keep recordings complete so they replay without the cache.
+            if let Some(cassette) = &self.cassette {
+                cassette.store(model, &original, &resp)?;
+            }
+            return Ok(resp);
+        }
+
+        let merged;
+        let (system_prompt, user_message) =
+            if self.backend.capabilities().system_prompt || system_prompt.is_empty() {
+                (system_prompt, user_message)
+            } else {
+                merged = format!("{system_prompt}\n\n{user_message}");
+                ("", merged.as_str())
+            };
+
+        let request = ChatRequest {
+            system_prompt,
+            user_message,
+            ..original
+        };
+
+        // Usage is unknown until the reply arrives, so reserve the worst case
+        // and let the permit refund the difference.
+        let estimated_tokens = ((system_prompt.len() + user_message.len()) / 4) as u32 + max_tokens;
+
+        let mut retry = 0;
+        loop {
+            let permit = match &self.limiter {
+                Some(limiter) => Some(limiter.acquire(estimated_tokens).await),
+                None => None,
+            };
+            let result = match on_delta {
+                Some(sink) => self.backend.chat_stream(&request, sink).await,
+                None => self.backend.chat(&request).await,
+            };
+            if let Some(permit) = permit {
+                let used = result.as_ref().map(|r| r.total_tokens).unwrap_or(0);
+                permit.settle(used);
+            }
+
+            let err = match result {
+                Ok(resp) => {
+                    self.add_usage(&resp);
+                    if let Some(cache) = &self.cache {
+                        cache.put(provider, model, &original, &resp);
+                    }
+                    if let Some(cassette) = &self.cassette {
+                        cassette.store(model, &original, &resp)?;
+                    }
+                    return Ok(resp);
+                }
+                Err(e) => e,
+            };
+            let Some((reason, retry_after)) = retry::retry_reason(&err) else {
+                return Err(err);
+            };
+            if retry >= self.retry.max_retries {
+                return Err(err.context(format!("giving up after {} retries", retry)));
+            }
+            retry += 1;
+
+            let delay = self.retry.delay(retry, retry_after, &mut rand::thread_rng());
+            warn!(
+                backend = self.backend.name(),
+                model = self.backend.model(),
+                attempt = retry + 1,
+                delay_ms = delay.as_millis() as u64,
+                "Retrying after {reason}"
+            );
+            self.retry_log
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(reason);
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+pub(crate) fn http_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(120))
+        .build()?)
+}
+
+pub(crate) fn estimate_tokens(content: &str) -> (u32, u32, u32) {
+    let est = (content.len() / 4) as u32;
+    (0, est, est)
+}
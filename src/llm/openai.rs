@@ -0,0 +1,212 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::{
+    estimate_tokens, sse, ApiError, BackendCapabilities, BackendSpec, ChatRequest, DeltaSink,
+    LlmBackend, LlmResponse,
+};
+
+/// OpenAI-compatible `/chat/completions` backend (OpenAI, Ollama, vLLM, ...).
+pub struct OpenAiBackend {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct OaiChatRequest {
+    model: String,
+    messages: Vec<OaiChatMessage>,
+    temperature: f64,
+    top_p: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OaiStreamOptions>,
+}
+
+#[derive(Serialize)]
+struct OaiStreamOptions {
+    include_usage: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct OaiChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct OaiChatResponseBody {
+    choices: Vec<OaiChoice>,
+    usage: Option<OaiUsage>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OaiChoice {
+    message: OaiChatMessage,
+}
+
+#[derive(Deserialize, Debug)]
+struct OaiStreamChunk {
+    #[serde(default)]
+    choices: Vec<OaiStreamChoice>,
+    usage: Option<OaiUsage>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OaiStreamChoice {
+    delta: OaiDelta,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct OaiDelta {
+    content: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OaiUsage {
+    prompt_tokens: Option<u32>,
+    completion_tokens: Option<u32>,
+    total_tokens: Option<u32>,
+}
+
+impl OpenAiBackend {
+    pub fn new(spec: &BackendSpec) -> Result<Self> {
+        Ok(Self {
+            client: super::http_client()?,
+            base_url: spec.base_url.trim_end_matches('/').to_string(),
+            api_key: spec.api_key.clone(),
+            model: spec.model.clone(),
+        })
+    }
+
+    async fn send(&self, request: &ChatRequest<'_>, stream: bool) -> Result<reqwest::Response> {
+        let url = format!("{}/chat/completions", self.base_url);
+
+        let request_body = OaiChatRequest {
+            model: self.model.clone(),
+            messages: vec![
+                OaiChatMessage {
+                    role: "system".into(),
+                    content: request.system_prompt.into(),
+                },
+                OaiChatMessage {
+                    role: "user".into(),
+                    content: request.user_message.into(),
+                },
+            ],
+            temperature: request.temperature,
+            top_p: request.top_p,
+            max_tokens: Some(request.max_tokens),
+            seed: request.seed,
+            stream,
+            stream_options: stream.then_some(OaiStreamOptions {
+                include_usage: true,
+            }),
+        };
+
+        debug!(url = %url, model = %self.model, provider = "openai", stream, "Sending chat completion request");
+
+        let http_resp = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if !http_resp.status().is_success() {
+            return Err(ApiError::from_response("OpenAI", http_resp).await.into());
+        }
+        Ok(http_resp)
+    }
+}
+
+#[async_trait]
+impl LlmBackend for OpenAiBackend {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn capabilities(&self) -> BackendCapabilities {
+        BackendCapabilities {
+            system_prompt: true,
+            top_p: true,
+            usage_reporting: true,
+            streaming: true,
+        }
+    }
+
+    async fn chat(&self, request: &ChatRequest<'_>) -> Result<LlmResponse> {
+        let http_resp = self.send(request, false).await?;
+        let body: OaiChatResponseBody = http_resp.json().await?;
+
+        let content = body
+            .choices
+            .first()
+            .map(|c| c.message.content.clone())
+            .unwrap_or_default();
+
+        Ok(into_response(content, body.usage))
+    }
+
+    async fn chat_stream(
+        &self,
+        request: &ChatRequest<'_>,
+        on_delta: &DeltaSink<'_>,
+    ) -> Result<LlmResponse> {
+        let http_resp = self.send(request, true).await?;
+
+        let mut content = String::new();
+        let mut usage = None;
+        sse::for_each_event(http_resp, |data| {
+            let chunk: OaiStreamChunk = serde_json::from_str(data)?;
+            if let Some(delta) = chunk.choices.first().and_then(|c| c.delta.content.as_deref()) {
+                if !delta.is_empty() {
+                    on_delta(delta);
+                    content.push_str(delta);
+                }
+            }
+            if chunk.usage.is_some() {
+                usage = chunk.usage;
+            }
+            Ok(())
+        })
+        .await?;
+
+        Ok(into_response(content, usage))
+    }
+}
+
+fn into_response(content: String, usage: Option<OaiUsage>) -> LlmResponse {
+    let (prompt_tokens, completion_tokens, total_tokens) = match usage {
+        Some(u) => (
+            u.prompt_tokens.unwrap_or(0),
+            u.completion_tokens.unwrap_or(0),
+            u.total_tokens.unwrap_or_else(|| {
+                u.prompt_tokens.unwrap_or(0) + u.completion_tokens.unwrap_or(0)
+            }),
+        ),
+        None => estimate_tokens(&content),
+    };
+
+    LlmResponse {
+        content,
+        prompt_tokens,
+        completion_tokens,
+        total_tokens,
+    }
+}
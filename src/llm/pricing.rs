@@ -0,0 +1,140 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// USD list price per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+}
+
+impl ModelPrice {
+    pub const fn new(input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        Self {
+            input_per_mtok,
+            output_per_mtok,
+        }
+    }
+
+    pub fn cost(&self, prompt_tokens: u32, completion_tokens: u32) -> f64 {
+        (prompt_tokens as f64 * self.input_per_mtok
+            + completion_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+/// Parses `"INPUT,OUTPUT"` (USD per million tokens), e.g. `"0.15,0.60"`.
+impl FromStr for ModelPrice {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (input, output) = s
+            .split_once(',')
+            .ok_or_else(|| anyhow!("expected INPUT,OUTPUT prices, got '{s}'"))?;
+        let input: f64 = input.trim().parse()?;
+        let output: f64 = output.trim().parse()?;
+        if !input.is_finite() || !output.is_finite() || input < 0.0 || output < 0.0 {
+            bail!("prices must be finite and not negative, got '{s}'");
+        }
+        Ok(Self::new(input, output))
+    }
+}
+
+/// Built-in list prices, matched by longest model-name prefix so dated
+/// snapshots (`gpt-4o-mini-2024-07-18`) resolve to their family.
+const BUILTIN_PRICES: &[(&str, ModelPrice)] = &[
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.60)),
+    ("gpt-4o", ModelPrice::new(2.50, 10.00)),
+    ("gpt-4.1-nano", ModelPrice::new(0.10, 0.40)),
+    ("gpt-4.1-mini", ModelPrice::new(0.40, 1.60)),
+    ("gpt-4.1", ModelPrice::new(2.00, 8.00)),
+    ("gpt-4-turbo", ModelPrice::new(10.00, 30.00)),
+    ("gpt-3.5-turbo", ModelPrice::new(0.50, 1.50)),
+    ("o3-mini", ModelPrice::new(1.10, 4.40)),
+    ("o4-mini", ModelPrice::new(1.10, 4.40)),
+    ("gemini-2.5-pro", ModelPrice::new(1.25, 10.00)),
+    ("gemini-2.5-flash", ModelPrice::new(0.30, 2.50)),
+    ("gemini-2.0-flash-lite", ModelPrice::new(0.075, 0.30)),
+    ("gemini-2.0-flash", ModelPrice::new(0.10, 0.40)),
+    ("gemini-1.5-pro", ModelPrice::new(1.25, 5.00)),
+    ("gemini-1.5-flash", ModelPrice::new(0.075, 0.30)),
+    ("claude-3-5-haiku", ModelPrice::new(0.80, 4.00)),
+    ("claude-3-haiku", ModelPrice::new(0.25, 1.25)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.00, 15.00)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.00, 15.00)),
+    ("claude-sonnet-4", ModelPrice::new(3.00, 15.00)),
+    ("claude-opus-4", ModelPrice::new(15.00, 75.00)),
+    ("claude-3-opus", ModelPrice::new(15.00, 75.00)),
+    ("mock", ModelPrice::new(0.0, 0.0)),
+];
+
+/// Model prices: user overrides first, then the built-in list.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PricingTable {
+    overrides: Vec<(String, ModelPrice)>,
+}
+
+impl PricingTable {
+    pub fn with_override(mut self, model: &str, price: ModelPrice) -> Self {
+        self.overrides.retain(|(m, _)| m != model);
+        self.overrides.push((model.to_string(), price));
+        self
+    }
+
+    /// User-supplied prices, in the order they were added.
+    pub fn overrides(&self) -> &[(String, ModelPrice)] {
+        &self.overrides
+    }
+
+    /// Parses a `MODEL=INPUT,OUTPUT` override.
+    pub fn parse_override(spec: &str) -> Result<(String, ModelPrice)> {
+        let (model, price) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow!("expected MODEL=INPUT,OUTPUT, got '{spec}'"))?;
+        let model = model.trim();
+        if model.is_empty() {
+            bail!("missing model name in '{spec}'");
+        }
+        Ok((model.to_string(), price.parse()?))
+    }
+
+    /// Returns `None` for models with no known price (e.g. local Ollama models).
+    pub fn lookup(&self, model: &str) -> Option<ModelPrice> {
+        let model = model.trim_start_matches("models/");
+        if let Some((_, price)) = self.overrides.iter().find(|(m, _)| m == model) {
+            return Some(*price);
+        }
+        BUILTIN_PRICES
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| *price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_prefix_wins_and_overrides_take_precedence() {
+        let table = PricingTable::default();
+        assert_eq!(
+            table.lookup("gpt-4o-mini-2024-07-18"),
+            Some(ModelPrice::new(0.15, 0.60))
+        );
+        assert_eq!(table.lookup("gpt-4o"), Some(ModelPrice::new(2.50, 10.00)));
+        assert_eq!(table.lookup("llama3.1:8b"), None);
+
+        let (model, price) = PricingTable::parse_override("gpt-4o=1,2").unwrap();
+        let table = table.with_override(&model, price);
+        assert_eq!(table.lookup("gpt-4o"), Some(ModelPrice::new(1.0, 2.0)));
+        assert!((price.cost(1_000_000, 500_000) - 2.0).abs() < 1e-9);
+
+        for bad in ["-1,2", "nan,2", "1,inf", "1,-inf", "1"] {
+            assert!(bad.parse::<ModelPrice>().is_err(), "{bad} should be rejected");
+        }
+    }
+}
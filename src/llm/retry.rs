@@ -0,0 +1,192 @@
+use std::fmt;
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A non-2xx reply from a provider, kept structured so the retry loop can
+/// tell rate limits and outages apart from bad requests.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub provider: &'static str,
+    pub status: u16,
+    pub retry_after: Option<Duration>,
+    pub body: String,
+}
+
+impl ApiError {
+    pub async fn from_response(provider: &'static str, resp: reqwest::Response) -> Self {
+        let status = resp.status().as_u16();
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = resp.text().await.unwrap_or_default();
+        Self {
+            provider,
+            status,
+            retry_after,
+            body,
+        }
+    }
+
+    /// 408, 409, 429 and 5xx (including Anthropic's 529 "overloaded") are
+    /// transient; any other 4xx means the request itself is wrong.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.status, 408 | 409 | 429) || self.status >= 500
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = reqwest::StatusCode::from_u16(self.status)
+            .map(|s| s.to_string())
+            .unwrap_or_else(|_| self.status.to_string());
+        write!(f, "{} API error ({}): {}", self.provider, status, self.body)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Longest `Retry-After` taken from a server; larger values are clamped.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(3600);
+
+/// `Retry-After` as delta-seconds, at most [`MAX_RETRY_AFTER`]. HTTP-date
+/// values are ignored and fall back to the regular backoff.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|s| s.is_finite() && *s >= 0.0)
+        .map(|s| {
+            Duration::try_from_secs_f64(s)
+                .unwrap_or(MAX_RETRY_AFTER)
+                .min(MAX_RETRY_AFTER)
+        })
+}
+
+/// Why a failed attempt is worth repeating, or `None` if it is not.
+pub fn retry_reason(err: &anyhow::Error) -> Option<(String, Option<Duration>)> {
+    for cause in err.chain() {
+        if let Some(api) = cause.downcast_ref::<ApiError>() {
+            return api
+                .is_retryable()
+                .then(|| (format!("HTTP {}", api.status), api.retry_after));
+        }
+        if let Some(http) = cause.downcast_ref::<reqwest::Error>() {
+            if http.is_timeout() {
+                return Some(("timeout".into(), None));
+            }
+            if http.is_connect() {
+                return Some(("connection error".into(), None));
+            }
+            return None;
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying.
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    /// Upper bound for both the exponential backoff and a server `Retry-After`.
+    pub max_delay: Duration,
+    /// Fraction of the delay randomised in either direction (0.0-1.0).
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based). A server-provided
+    /// `Retry-After` wins over the computed backoff.
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>, rng: &mut impl Rng) -> Duration {
+        if let Some(server) = retry_after {
+            return server.min(self.max_delay);
+        }
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return exp;
+        }
+        let factor = rng.gen_range(1.0 - jitter..=1.0 + jitter);
+        exp.mul_f64(factor).min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            jitter: 0.0,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(policy.delay(1, None, &mut rng), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, None, &mut rng), Duration::from_millis(200));
+        assert_eq!(policy.delay(3, None, &mut rng), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn retry_after_overrides_backoff() {
+        let policy = RetryPolicy::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let server = parse_retry_after(" 7 ");
+        assert_eq!(server, Some(Duration::from_secs(7)));
+        assert_eq!(policy.delay(1, server, &mut rng), Duration::from_secs(7));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn oversized_retry_after_is_clamped() {
+        assert_eq!(parse_retry_after("1e300"), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("86400"), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("-1"), None);
+    }
+
+    #[test]
+    fn only_transient_statuses_retry() {
+        let err = |status| ApiError {
+            provider: "Test",
+            status,
+            retry_after: None,
+            body: String::new(),
+        };
+        assert!(err(429).is_retryable());
+        assert!(err(503).is_retryable());
+        assert!(err(529).is_retryable());
+        assert!(!err(400).is_retryable());
+        assert!(!err(401).is_retryable());
+        assert!(retry_reason(&anyhow::Error::new(err(404))).is_none());
+    }
+}
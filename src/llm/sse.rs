@@ -0,0 +1,88 @@
+use anyhow::Result;
+use futures::{Stream, StreamExt};
+
+/// Reads a `text/event-stream` body and hands each event's `data` payload to
+/// `on_event`. Stops early if the payload is `[DONE]` (OpenAI's terminator).
+pub async fn for_each_event(
+    resp: reqwest::Response,
+    on_event: impl FnMut(&str) -> Result<()>,
+) -> Result<()> {
+    read_events(resp.bytes_stream(), on_event).await
+}
+
+/// [`for_each_event`] over raw body chunks. Bytes are buffered until a full
+/// line has arrived, so a UTF-8 character split across chunks survives.
+async fn read_events<B, E>(
+    stream: impl Stream<Item = std::result::Result<B, E>>,
+    mut on_event: impl FnMut(&str) -> Result<()>,
+) -> Result<()>
+where
+    B: AsRef<[u8]>,
+    E: Into<anyhow::Error>,
+{
+    let mut stream = std::pin::pin!(stream);
+    let mut buf: Vec<u8> = Vec::new();
+    let mut data = String::new();
+
+    while let Some(chunk) = stream.next().await {
+        buf.extend_from_slice(chunk.map_err(Into::into)?.as_ref());
+
+        while let Some(newline) = buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            if line.is_empty() {
+                if dispatch(&mut data, &mut on_event)? {
+                    return Ok(());
+                }
+            } else if let Some(payload) = line.strip_prefix("data:") {
+                if !data.is_empty() {
+                    data.push('\n');
+                }
+                data.push_str(payload.strip_prefix(' ').unwrap_or(payload));
+            }
+        }
+    }
+
+    dispatch(&mut data, &mut on_event)?;
+    Ok(())
+}
+
+/// Returns `true` once the terminator has been seen.
+fn dispatch(data: &mut String, on_event: &mut impl FnMut(&str) -> Result<()>) -> Result<bool> {
+    if data.is_empty() {
+        return Ok(false);
+    }
+    let payload = std::mem::take(data);
+    if payload.trim() == "[DONE]" {
+        return Ok(true);
+    }
+    on_event(&payload)?;
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn multibyte_character_split_across_chunks_is_kept() {
+        let body = "data: caf\u{e9}\n\ndata: [DONE]\n\n".as_bytes();
+        let split = body.iter().position(|&b| b == 0xc3).unwrap() + 1;
+        let chunks = vec![
+            Ok::<_, anyhow::Error>(body[..split].to_vec()),
+            Ok(body[split..].to_vec()),
+        ];
+
+        let mut events = Vec::new();
+        read_events(futures::stream::iter(chunks), |data| {
+            events.push(data.to_string());
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(events, vec!["caf\u{e9}"]);
+    }
+}
@@ -1,17 +1,33 @@
+use std::path::Path;
+
 use anyhow::Result;
 use clap::Parser;
 use colored::*;
+use serde::Serialize;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
-use emas::arena::Arena;
-use emas::config::{Cli, Config};
+use emas::arena::{
+    Arena, ArenaEvent, EvolutionResult, RunControl, RunState, StopReason, TeamScore, Usage,
+};
+use emas::archive::Niche;
+use emas::bench::{bench_problem, BenchResult};
+use emas::checkpoint::Checkpoint;
+use emas::config::{BenchArgs, Cli, Command, Config, ConfigCommand, HistoryCommand, OutputFormat};
+use emas::config_file::{self, FileConfig};
+use emas::genotype::Genotype;
+use emas::history::{RunRecord, RunStore};
+use emas::llm::Cassette;
+use emas::orchestrator::FitnessScore;
+use emas::report::write_report;
+use emas::server::{serve, ServeOptions};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let _ = dotenvy::dotenv();
 
-    let cli = Cli::parse();
+    let command = Cli::parse().into_command();
 
-    if cli.tui {
+    if let Command::Tui(args) = &command {
         tracing_subscriber::fmt()
             .with_env_filter(
                 tracing_subscriber::EnvFilter::try_from_default_env()
@@ -21,26 +37,467 @@ async fn main() -> Result<()> {
             .with_writer(std::io::sink)
             .init();
 
-        return emas::tui::run_tui(&cli).await;
+        return emas::tui::run_tui(args).await;
     }
 
+    let output = match &command {
+        Command::Run(args) => args.output,
+        Command::Resume { output, .. } => *output,
+        _ => OutputFormat::Human,
+    };
+    // Keep stdout clean for machine-readable output.
+    let log_writer = if output == OutputFormat::Human {
+        BoxMakeWriter::new(std::io::stdout)
+    } else {
+        BoxMakeWriter::new(std::io::stderr)
+    };
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
         )
         .with_target(false)
+        .with_writer(log_writer)
         .init();
 
-    let problem = cli
-        .problem
-        .as_deref()
-        .ok_or_else(|| anyhow::anyhow!("Problem is required in CLI mode. Use --tui for interactive mode."))?;
+    match command {
+        Command::Run(args) => {
+            let problem = args.input.load()?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Problem is required. Use `emas run \"<problem>\"`, --problem-file, or `emas tui` for interactive mode."
+                )
+            })?;
+            for warning in &problem.warnings {
+                tracing::warn!("{warning}");
+            }
+            let config = Config::from_cli(&args.llm, &args.evolution)?;
+            let arena = Arena::new(config)?;
+            let state = arena.initial_state();
+            run(&arena, &problem.text, state, Usage::default(), output, args.report.as_deref()).await
+        }
+        Command::Resume {
+            checkpoint,
+            api_key,
+            judge_api_key,
+            report,
+            ..
+        } => {
+            let Checkpoint {
+                problem,
+                mut config,
+                state,
+                usage,
+                ..
+            } = Checkpoint::load(&checkpoint)?;
+            config.restore_api_keys(api_key, judge_api_key)?;
+            config.checkpoint_path = Some(checkpoint);
+            let arena = Arena::new(config)?;
+            run(&arena, &problem, state, usage, output, report.as_deref()).await
+        }
+        Command::Bench(args) => bench(args).await,
+        Command::Serve(args) => {
+            let mut config = Config::from_cli(&args.llm, &args.evolution)?;
+            config.checkpoint_path = None;
+            let options = ServeOptions {
+                addr: args.addr,
+                workers: args.workers,
+                queue_size: args.queue_size,
+                keep_jobs: args.keep_jobs,
+            };
+            serve(config, options).await
+        }
+        Command::Inspect { path } => inspect(&path),
+        Command::History { action } => history(action),
+        Command::Config { action } => config(action),
+        Command::Tui(_) => unreachable!("handled above"),
+    }
+}
+
+async fn run(
+    arena: &Arena,
+    problem: &str,
+    state: RunState,
+    prior: Usage,
+    output: OutputFormat,
+    report: Option<&Path>,
+) -> Result<()> {
+    if output == OutputFormat::Human {
+        let result = arena.run_from(problem, state, prior).await?;
+        print_result(&result);
+        match save_to_history(problem, &arena.config, &result) {
+            Ok(record) => println!(
+                "   {}",
+                format!("Saved as run {} (emas history show {})", record.id, record.id).dimmed()
+            ),
+            Err(e) => println!("   {}: run not saved to history: {e:#}", "Warning".yellow()),
+        }
+        if let Some(path) = report {
+            match write_report(path, problem, &result) {
+                Ok(()) => println!(
+                    "   {}",
+                    format!("Report written to {}", path.display()).dimmed()
+                ),
+                Err(e) => println!("   {}: {e:#}", "Warning".yellow()),
+            }
+        }
+        return Ok(());
+    }
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let control = RunControl::default();
+    let run = arena.resume_with_progress(problem, state, prior, tx, &control);
+    let stream = async {
+        while let Some(event) = rx.recv().await {
+            if output == OutputFormat::Jsonl {
+                print_json_line(&event);
+            }
+        }
+    };
+    let (result, ()) = tokio::join!(run, stream);
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            if output == OutputFormat::Jsonl {
+                print_json_line(&ArenaEvent::Error(format!("{e:#}")));
+            }
+            return Err(e);
+        }
+    };
+
+    let run_id = match save_to_history(problem, &arena.config, &result) {
+        Ok(record) => Some(record.id),
+        Err(e) => {
+            eprintln!("Warning: run not saved to history: {e:#}");
+            None
+        }
+    };
+    if let Some(path) = report {
+        if let Err(e) = write_report(path, problem, &result) {
+            eprintln!("Warning: {e:#}");
+        }
+    }
+    match output {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&JsonReport::new(problem, &result, run_id))?
+        ),
+        _ => print_json_line(&ArenaEvent::Completed(result)),
+    }
+    Ok(())
+}
+
+fn print_json_line(event: &ArenaEvent) {
+    match serde_json::to_string(event) {
+        Ok(line) => println!("{line}"),
+        Err(e) => eprintln!("Warning: could not serialise event: {e}"),
+    }
+}
+
+/// The `--output json` document.
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    problem: &'a str,
+    run_id: Option<String>,
+    synthesis: &'a str,
+    best_team: JsonTeam<'a>,
+    score: &'a FitnessScore,
+    generations: &'a [Vec<TeamScore>],
+    generations_run: usize,
+    converged: bool,
+    stop_reason: String,
+    total_tokens: u64,
+    total_cost: f64,
+    seed: u64,
+    pareto_front: Vec<JsonParetoMember<'a>>,
+    archive: Vec<JsonArchiveCell<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonArchiveCell<'a> {
+    niche: &'a Niche,
+    name: &'a str,
+    generation: usize,
+    score: &'a FitnessScore,
+}
+
+#[derive(Serialize)]
+struct JsonParetoMember<'a> {
+    name: &'a str,
+    generation: usize,
+    score: &'a FitnessScore,
+    tokens: u32,
+}
+
+#[derive(Serialize)]
+struct JsonTeam<'a> {
+    name: &'a str,
+    generation: usize,
+    agents: Vec<&'a Genotype>,
+}
+
+impl<'a> JsonReport<'a> {
+    fn new(problem: &'a str, result: &'a EvolutionResult, run_id: Option<String>) -> Self {
+        Self {
+            problem,
+            run_id,
+            synthesis: &result.synthesis,
+            best_team: JsonTeam {
+                name: &result.best_team.name,
+                generation: result.best_team.generation,
+                agents: result.best_team.agents.iter().map(|a| &a.genotype).collect(),
+            },
+            score: &result.best_score,
+            generations: &result.generation_scores,
+            generations_run: result.generations_run,
+            converged: result.stop_reason == StopReason::Converged,
+            stop_reason: result.stop_reason.to_string(),
+            total_tokens: result.total_tokens,
+            total_cost: result.total_cost,
+            seed: result.seed,
+            pareto_front: result
+                .pareto_front
+                .iter()
+                .map(|m| JsonParetoMember {
+                    name: &m.team.name,
+                    generation: m.team.generation,
+                    score: &m.score,
+                    tokens: m.output.total_tokens,
+                })
+                .collect(),
+            archive: result
+                .archive
+                .ranked()
+                .into_iter()
+                .map(|cell| JsonArchiveCell {
+                    niche: &cell.niche,
+                    name: &cell.team.name,
+                    generation: cell.team.generation,
+                    score: &cell.score,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn save_to_history(problem: &str, config: &Config, result: &EvolutionResult) -> Result<RunRecord> {
+    RunStore::open_default().and_then(|store| store.save(problem, config, result))
+}
+
+fn history(action: HistoryCommand) -> Result<()> {
+    let store = RunStore::open_default()?;
+    match action {
+        HistoryCommand::List => {
+            let records = store.list()?;
+            if records.is_empty() {
+                println!("No runs stored yet.");
+            }
+            for record in records {
+                println!(
+                    "{}  {:>9}  {:>5}  {}  {}",
+                    record.id.cyan(),
+                    record.age().dimmed(),
+                    format!("{:.2}", record.result.best_score.total).green(),
+                    record.result.best_team.name.bold(),
+                    truncate(&record.problem, 60),
+                );
+            }
+        }
+        HistoryCommand::Show { id } => print_record(&store.load(&id)?),
+        HistoryCommand::Rm { id } => println!("Removed run {}", store.remove(&id)?),
+    }
+    Ok(())
+}
+
+async fn bench(args: BenchArgs) -> Result<()> {
+    let config = Config::from_cli(&args.llm, &args.evolution)?;
+    println!(
+        "{} {} problem(s) x {} run(s) on {} / {}",
+        "Benchmarking".bold().cyan(),
+        args.problems.len(),
+        args.runs,
+        config.provider,
+        config.model,
+    );
+    let mut results = Vec::with_capacity(args.problems.len());
+    for problem in &args.problems {
+        println!("  {} {}", "->".dimmed(), truncate(problem, 60));
+        results.push(bench_problem(&config, problem, args.runs).await?);
+    }
+    print_bench(&results);
+    Ok(())
+}
+
+fn print_bench(results: &[BenchResult]) {
+    println!();
+    println!(
+        "{}",
+        format!(
+            "  {:<40}  {:>11}  {:>5}  {:>5}  {:>5}  {:>9}  {:>8}  {:>7}",
+            "Problem", "Score", "Min", "Max", "Gens", "Tokens", "Cost", "Time"
+        )
+        .bold()
+    );
+    for r in results {
+        println!(
+            "  {:<40}  {:>11}  {:>5.2}  {:>5.2}  {:>5.1}  {:>9}  {:>8}  {:>6.1}s",
+            truncate(&r.problem, 40),
+            format!("{:.2} +/- {:.2}", r.mean_score(), r.score_std_dev()).green(),
+            r.min_score(),
+            r.max_score(),
+            r.mean_generations(),
+            r.total_tokens(),
+            format!("${:.4}", r.total_cost()),
+            r.mean_elapsed().as_secs_f64(),
+        );
+    }
+}
+
+fn inspect(path: &Path) -> Result<()> {
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        let tallies = Cassette::summarize(path)?;
+        println!("{} {}", "Cassette".bold(), path.display());
+        if tallies.is_empty() {
+            println!("  (empty)");
+        }
+        for (model, tally) in tallies {
+            println!(
+                "  {}  {} calls, {} prompt + {} completion tokens",
+                model.cyan(),
+                tally.calls,
+                tally.prompt_tokens,
+                tally.completion_tokens,
+            );
+        }
+        return Ok(());
+    }
 
-    let config = Config::from_cli(&cli)?;
-    let arena = Arena::new(config);
-    let result = arena.run(problem).await?;
+    let checkpoint = Checkpoint::load(path)?;
+    let config = &checkpoint.config;
+    let state = &checkpoint.state;
+    println!("{} {}", "Checkpoint".bold(), path.display());
+    println!("  {} {}", "Problem:".bold(), truncate(&checkpoint.problem, 70));
+    println!(
+        "  {} {} / {}  (judge {} / {})",
+        "Model:".bold(),
+        config.provider,
+        config.model,
+        config.judge_provider,
+        config.judge_model,
+    );
+    println!(
+        "  {} {} of {} generations done{}",
+        "Progress:".bold(),
+        state.next_gen,
+        config.max_generations,
+        if state.converged { ", converged" } else { "" },
+    );
+    println!(
+        "  {} {} tokens, ${:.4}, {:.0}s",
+        "Usage:".bold(),
+        checkpoint.usage.tokens,
+        checkpoint.usage.cost,
+        checkpoint.usage.elapsed.as_secs_f64(),
+    );
+    if let Some(seed) = config.seed {
+        println!("  {} {}", "Seed:".bold(), seed);
+    }
+    if let Some((team, _, score)) = &state.best_ever {
+        println!(
+            "  {} {} (generation {}, score {:.2})",
+            "Best so far:".bold(),
+            team.name.green(),
+            team.generation,
+            score.total,
+        );
+    }
+    println!();
+    println!("{}", "  Population:".bold());
+    for team in &state.population {
+        let strategies: Vec<String> = team
+            .agents
+            .iter()
+            .map(|a| a.genotype.strategy.to_string())
+            .collect();
+        println!(
+            "   |-- {} (generation {}): {}",
+            team.name.white().bold(),
+            team.generation,
+            strategies.join(", ").dimmed(),
+        );
+    }
+    Ok(())
+}
 
+fn config(action: ConfigCommand) -> Result<()> {
+    match action {
+        ConfigCommand::Show { llm, mut evolution } => {
+            evolution.no_save = true;
+            let config = Config::from_cli(&llm, &evolution)?;
+            let files = config_file::load(evolution.profile.as_deref())?;
+            if files.paths.is_empty() {
+                let user = config_file::user_path()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default();
+                println!(
+                    "# No config files found ({} or {user})",
+                    config_file::PROJECT_FILE
+                );
+            }
+            for path in &files.paths {
+                println!("# Read {}", path.display());
+            }
+            if let Some(profile) = &evolution.profile {
+                println!("# Profile: {profile}");
+            }
+            for line in config_file::masked_keys(&config) {
+                println!("{line}");
+            }
+            print!("{}", toml::to_string_pretty(&FileConfig::from_config(&config))?);
+        }
+    }
+    Ok(())
+}
+
+fn print_record(record: &RunRecord) {
+    println!("{} {} ({})", "Run".bold(), record.id.cyan(), record.age());
+    println!("  {} {}", "Problem:".bold(), record.problem);
+    println!(
+        "  {} {} / {}  (judge {} / {})",
+        "Model:".bold(),
+        record.config.provider,
+        record.config.model,
+        record.config.judge_provider,
+        record.config.judge_model,
+    );
+    println!(
+        "  {} {} teams x {} agents, {} generations max",
+        "Setup:".bold(),
+        record.config.population_size,
+        record.config.team_size,
+        record.config.max_generations,
+    );
+    println!();
+    println!("{}", "  Scores per generation:".bold());
+    for (gen, scores) in record.result.generation_scores.iter().enumerate() {
+        let ranked: Vec<String> = scores
+            .iter()
+            .map(|s| format!("{} {:.2}", s.name, s.total))
+            .collect();
+        println!("   {:>3}  {}", gen + 1, ranked.join(", "));
+    }
+    print_result(&record.result);
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    let line = s.lines().next().unwrap_or_default();
+    if line.chars().count() > max {
+        format!("{}...", line.chars().take(max).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn print_result(result: &EvolutionResult) {
     println!();
     println!(
         "{}",
@@ -58,15 +515,49 @@ async fn main() -> Result<()> {
         "   Generations run: {}",
         result.generations_run.to_string().cyan(),
     );
+    println!(
+        "   Stopped: {}",
+        result.stop_reason.to_string().cyan(),
+    );
+    println!(
+        "   Total cost: {}",
+        format!("${:.4}", result.total_cost).cyan(),
+    );
+    println!("   Seed: {}", result.seed.to_string().cyan());
+    if result.pareto_front.len() > 1 {
+        println!("{}", "   Pareto front (quality / consistency / efficiency):".bold());
+        for m in &result.pareto_front {
+            println!(
+                "   |-- {:<18} {:.2}  (Q:{:.1} C:{:.1} E:{:.1}, {} tokens)",
+                m.team.name,
+                m.score.total,
+                m.score.quality,
+                m.score.consistency,
+                m.score.efficiency,
+                m.output.total_tokens,
+            );
+        }
+    }
+    if result.archive.len() > 1 {
+        println!(
+            "{}",
+            "   MAP-Elites archive (answer length, strategy, temperature):".bold()
+        );
+        for cell in result.archive.ranked() {
+            println!(
+                "   |-- {:<40} {:<18} {:.2}",
+                cell.niche.to_string(),
+                cell.team.name,
+                cell.score.total,
+            );
+        }
+    }
     println!();
 
     println!("{}", "   Team composition:".bold());
-    for (i, agent) in result.best_team.agents.iter().enumerate() {
-        let is_last = i == result.best_team.agents.len() - 1;
-        let branch = if is_last { "|--" } else { "|--" };
+    for agent in &result.best_team.agents {
         println!(
-            "   {} {} ({}, temp {:.2})",
-            branch,
+            "   |-- {} ({}, temp {:.2})",
             agent.genotype.name.white().bold(),
             agent.genotype.strategy.to_string().dimmed(),
             agent.genotype.temperature,
@@ -94,6 +585,4 @@ async fn main() -> Result<()> {
             .bold()
             .bright_white()
     );
-
-    Ok(())
 }
@@ -1,20 +1,32 @@
 use std::collections::HashSet;
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use crate::config::Config;
 use crate::llm::LlmClient;
 use crate::team::TeamOutput;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FitnessScore {
     pub quality: f64,
     pub consistency: f64,
     pub efficiency: f64,
     pub diversity_penalty: f64,
     pub total: f64,
+    /// Every judge's critique joined as `"[Persona] text\n\n[Persona] text"`.
     pub judge_critique: String,
+    /// The same critiques kept apart, one per judge.
+    #[serde(default)]
+    pub critiques: Vec<JudgeCritique>,
+}
+
+/// One judge's verdict text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgeCritique {
+    pub persona: String,
+    pub text: String,
 }
 
 impl std::fmt::Display for FitnessScore {
@@ -35,7 +47,7 @@ impl std::fmt::Display for FitnessScore {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ConclusionHistory {
     entries: Vec<(HashSet<String>, f64)>,
 }
@@ -167,7 +179,7 @@ impl Orchestrator {
         generation: usize,
         conclusion_history: &ConclusionHistory,
     ) -> Result<FitnessScore> {
-        let (quality, judge_critique) =
+        let (quality, judge_critique, critiques) =
             self.evaluate_quality(output, problem, llm, config, generation).await?;
 
         let consistency = self.evaluate_consistency(output);
@@ -190,6 +202,7 @@ impl Orchestrator {
             diversity_penalty,
             total,
             judge_critique,
+            critiques,
         })
     }
 
@@ -210,9 +223,9 @@ impl Orchestrator {
         llm: &LlmClient,
         config: &Config,
         generation: usize,
-    ) -> Result<(f64, String)> {
+    ) -> Result<(f64, String, Vec<JudgeCritique>)> {
         if output.agent_outputs.is_empty() {
-            return Ok((1.0, "No agent outputs to evaluate.".into()));
+            return Ok((1.0, "No agent outputs to evaluate.".into(), Vec::new()));
         }
 
         let mut agent_section = String::new();
@@ -243,9 +256,19 @@ impl Orchestrator {
             "[{}] {}\n\n[{}] {}",
             p1.name, c1, p2.name, c2
         );
+        let critiques = vec![
+            JudgeCritique {
+                persona: p1.name.to_string(),
+                text: c1,
+            },
+            JudgeCritique {
+                persona: p2.name.to_string(),
+                text: c2,
+            },
+        ];
 
         debug!(quality = score, persona_a = p1.name, persona_b = p2.name, "Quality evaluation (dual judge)");
-        Ok((score, critique))
+        Ok((score, critique, critiques))
     }
 
     async fn run_judge(
@@ -371,15 +394,9 @@ fn parse_quality_score(response: &str) -> (f64, String) {
     }
 
     let trimmed = response.trim();
-    let json_str = if let Some(start) = trimmed.find('{') {
-        if let Some(end) = trimmed.rfind('}') {
-            Some(&trimmed[start..=end])
-        } else {
-            None
-        }
-    } else {
-        None
-    };
+    let json_str = trimmed
+        .find('{')
+        .and_then(|start| trimmed.rfind('}').map(|end| &trimmed[start..=end]));
 
     if let Some(json_str) = json_str {
         if let Ok(v) = serde_json::from_str::<serde_json::Value>(json_str) {
@@ -0,0 +1,157 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// Default cap on the bytes of problem text plus attachments sent to the
+/// models.
+pub const DEFAULT_MAX_CONTEXT_BYTES: usize = 100_000;
+
+/// A file embedded in the problem text with `--context`.
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub name: String,
+    pub content: String,
+}
+
+impl Attachment {
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| {
+            format!("reading context file {} (must be UTF-8 text)", path.display())
+        })?;
+        Ok(Self {
+            name: path.display().to_string(),
+            content,
+        })
+    }
+}
+
+/// The text agents, judges and synthesis see, plus a note for every
+/// truncated or omitted part.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub text: String,
+    pub warnings: Vec<String>,
+}
+
+/// Reads the problem from the positional argument or `--problem-file`,
+/// where `-` means stdin. `None` if neither was given.
+pub fn read_source(positional: Option<&str>, file: Option<&Path>) -> Result<Option<String>> {
+    let text = match (positional, file) {
+        (Some("-"), _) => read_stdin()?,
+        (Some(text), _) => text.to_string(),
+        (None, Some(path)) if path == Path::new("-") => read_stdin()?,
+        (None, Some(path)) => fs::read_to_string(path)
+            .with_context(|| format!("reading problem file {}", path.display()))?,
+        (None, None) => return Ok(None),
+    };
+    if text.trim().is_empty() {
+        bail!("Problem cannot be empty");
+    }
+    Ok(Some(text))
+}
+
+fn read_stdin() -> Result<String> {
+    let mut text = String::new();
+    std::io::stdin()
+        .read_to_string(&mut text)
+        .context("reading problem from stdin")?;
+    Ok(text)
+}
+
+/// Reads every `--context` file and embeds it into `problem`.
+pub fn attach(problem: &str, context: &[PathBuf], max_bytes: usize) -> Result<Problem> {
+    let attachments = context
+        .iter()
+        .map(|path| Attachment::read(path))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(compose(problem, &attachments, max_bytes))
+}
+
+/// Appends `attachments` to `problem` in order, within `max_bytes` overall.
+/// The problem is budgeted first; the part that crosses the limit is cut
+/// on a line boundary where possible and everything after it is omitted.
+pub fn compose(problem: &str, attachments: &[Attachment], max_bytes: usize) -> Problem {
+    let mut warnings = Vec::new();
+    let (mut text, cut) = clip(problem.trim_end(), max_bytes, "Problem", &mut warnings);
+    let mut remaining = if cut { 0 } else { max_bytes - text.len() };
+
+    if !attachments.is_empty() {
+        text.push_str("\n\n=== Attached context ===\n");
+    }
+    for attachment in attachments {
+        if remaining == 0 {
+            warnings.push(format!(
+                "Context file {} omitted: the {max_bytes}-byte limit was reached",
+                attachment.name
+            ));
+            text.push_str(&format!("\n[{} omitted: size limit reached]\n", attachment.name));
+            continue;
+        }
+        let (content, cut) = clip(
+            attachment.content.trim_end(),
+            remaining,
+            &format!("Context file {}", attachment.name),
+            &mut warnings,
+        );
+        remaining = if cut { 0 } else { remaining - content.len() };
+        text.push_str(&format!(
+            "\n--- {name} ---\n{content}\n--- end of {name} ---\n",
+            name = attachment.name
+        ));
+    }
+    Problem { text, warnings }
+}
+
+/// `s` cut to at most `max` bytes, with a marker and a warning if it was
+/// cut. The flag tells whether it was.
+fn clip(s: &str, max: usize, what: &str, warnings: &mut Vec<String>) -> (String, bool) {
+    if s.len() <= max {
+        return (s.to_string(), false);
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    if let Some(newline) = s[..end].rfind('\n') {
+        end = newline;
+    }
+    warnings.push(format!(
+        "{what} truncated to {end} of {} bytes (limit --max-context-bytes)",
+        s.len()
+    ));
+    (
+        format!("{}\n[... truncated {} bytes]", &s[..end], s.len() - end),
+        true,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachments_share_the_byte_budget_in_order() {
+        let attachment = |name: &str, content: &str| Attachment {
+            name: name.into(),
+            content: content.into(),
+        };
+        let attachments = [
+            attachment("spec.md", "line one\nline two\nline three"),
+            attachment("main.rs", "fn main() {}"),
+        ];
+
+        let full = compose("Review this.", &attachments, DEFAULT_MAX_CONTEXT_BYTES);
+        assert!(full.warnings.is_empty());
+        assert!(full.text.starts_with("Review this.\n\n=== Attached context ==="));
+        assert!(full.text.contains("--- main.rs ---\nfn main() {}\n--- end of main.rs ---"));
+
+        // 12 bytes of problem leave 20 for spec.md, cut after "line two".
+        let clipped = compose("Review this.", &attachments, 32);
+        assert_eq!(clipped.warnings.len(), 2);
+        assert!(clipped.text.contains("line one\nline two\n[... truncated 11 bytes]"));
+        assert!(!clipped.text.contains("fn main"));
+        assert!(clipped.text.contains("[main.rs omitted: size limit reached]"));
+    }
+}
@@ -0,0 +1,546 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::arena::{EvolutionResult, TeamScore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// HTML for `.html` / `.htm`, Markdown for anything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                Self::Html
+            }
+            _ => Self::Markdown,
+        }
+    }
+}
+
+/// Renders a report and writes it to `path`, picking the format from the extension.
+pub fn write_report(path: &Path, problem: &str, result: &EvolutionResult) -> Result<()> {
+    let report = render(problem, result, ReportFormat::from_path(path));
+    fs::write(path, report).with_context(|| format!("writing report {}", path.display()))
+}
+
+/// A self-contained document covering the problem, every generation's
+/// scores, the fitness trajectory, the winning team with its raw answers and
+/// judge critiques, and the final synthesis.
+pub fn render(problem: &str, result: &EvolutionResult, format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Markdown => render_markdown(problem, result),
+        ReportFormat::Html => render_html(problem, result),
+    }
+}
+
+/// `(generation, best, mean)` for each evaluated generation.
+fn trajectory(result: &EvolutionResult) -> Vec<(usize, f64, f64)> {
+    result
+        .generation_scores
+        .iter()
+        .enumerate()
+        .filter(|(_, scores)| !scores.is_empty())
+        .map(|(i, scores)| {
+            let best = scores.iter().map(|s| s.total).fold(f64::MIN, f64::max);
+            let mean = scores.iter().map(|s| s.total).sum::<f64>() / scores.len() as f64;
+            (i + 1, best, mean)
+        })
+        .collect()
+}
+
+/// `(persona, text)` of each judge's critique of the winner. Runs saved
+/// before critiques were stored apart show the joined text under "Judges".
+fn judge_critiques(result: &EvolutionResult) -> Vec<(&str, &str)> {
+    let score = &result.best_score;
+    if score.critiques.is_empty() {
+        return Some(("Judges", score.judge_critique.trim()))
+            .filter(|(_, text)| !text.is_empty())
+            .into_iter()
+            .collect();
+    }
+    score
+        .critiques
+        .iter()
+        .map(|c| (c.persona.as_str(), c.text.trim()))
+        .filter(|(_, text)| !text.is_empty())
+        .collect()
+}
+
+fn summary_rows(result: &EvolutionResult) -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "Winner",
+            format!(
+                "{} (generation {})",
+                result.best_team.name, result.best_team.generation
+            ),
+        ),
+        ("Score", format!("{:.2} / 10", result.best_score.total)),
+        ("Generations run", result.generations_run.to_string()),
+        ("Stopped", result.stop_reason.to_string()),
+        ("Tokens", result.total_tokens.to_string()),
+        ("Cost", format!("${:.4}", result.total_cost)),
+        ("Seed", result.seed.to_string()),
+    ]
+}
+
+fn score_row(s: &TeamScore) -> [String; 6] {
+    [
+        s.name.clone(),
+        format!("{:.2}", s.total),
+        format!("{:.1}", s.quality),
+        format!("{:.1}", s.consistency),
+        format!("{:.1}", s.efficiency),
+        format!("{:.1}", s.diversity_penalty),
+    ]
+}
+
+const SCORE_HEADERS: [&str; 6] = [
+    "Team",
+    "Total",
+    "Quality",
+    "Consistency",
+    "Efficiency",
+    "Diversity penalty",
+];
+
+const PARETO_HEADERS: [&str; 6] = [
+    "Team",
+    "Total",
+    "Quality",
+    "Consistency",
+    "Efficiency",
+    "Tokens",
+];
+
+fn pareto_rows(result: &EvolutionResult) -> Vec<[String; 6]> {
+    result
+        .pareto_front
+        .iter()
+        .map(|m| {
+            [
+                format!("{} (generation {})", m.team.name, m.team.generation),
+                format!("{:.2}", m.score.total),
+                format!("{:.1}", m.score.quality),
+                format!("{:.1}", m.score.consistency),
+                format!("{:.1}", m.score.efficiency),
+                m.output.total_tokens.to_string(),
+            ]
+        })
+        .collect()
+}
+
+const ARCHIVE_HEADERS: [&str; 5] = ["Answer length", "Strategy", "Temperature", "Team", "Total"];
+
+fn archive_rows(result: &EvolutionResult) -> Vec<[String; 5]> {
+    result
+        .archive
+        .ranked()
+        .into_iter()
+        .map(|cell| {
+            [
+                cell.niche.length.to_string(),
+                cell.niche.strategy.to_string(),
+                cell.niche.temperature.to_string(),
+                format!("{} (generation {})", cell.team.name, cell.team.generation),
+                format!("{:.2}", cell.score.total),
+            ]
+        })
+        .collect()
+}
+
+// Markdown
+
+fn md_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+fn md_bar(score: f64) -> String {
+    let filled = (score * 2.0).round().clamp(0.0, 20.0) as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(20 - filled))
+}
+
+fn render_markdown(problem: &str, result: &EvolutionResult) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# EMAS Run Report\n");
+    let _ = writeln!(md, "## Problem\n");
+    for line in problem.lines() {
+        let _ = writeln!(md, "> {line}");
+    }
+    let _ = writeln!(md, "\n| | |\n|---|---|");
+    for (label, value) in summary_rows(result) {
+        let _ = writeln!(md, "| {label} | {} |", md_cell(&value));
+    }
+
+    let _ = writeln!(md, "\n## Fitness Trajectory\n");
+    let _ = writeln!(md, "| Generation | Best | Mean | |\n|---:|---:|---:|---|");
+    for (gen, best, mean) in trajectory(result) {
+        let _ = writeln!(md, "| {gen} | {best:.2} | {mean:.2} | `{}` |", md_bar(best));
+    }
+
+    let _ = writeln!(md, "\n## Scores per Generation");
+    for (i, scores) in result.generation_scores.iter().enumerate() {
+        let _ = writeln!(md, "\n### Generation {}\n", i + 1);
+        let _ = writeln!(md, "| {} |", SCORE_HEADERS.join(" | "));
+        let _ = writeln!(md, "|---|---:|---:|---:|---:|---:|");
+        for s in scores {
+            let row: Vec<String> = score_row(s).iter().map(|c| md_cell(c)).collect();
+            let _ = writeln!(md, "| {} |", row.join(" | "));
+        }
+    }
+
+    let pareto = pareto_rows(result);
+    if !pareto.is_empty() {
+        let _ = writeln!(md, "\n## Pareto Front\n");
+        let _ = writeln!(md, "Teams no other team beat on quality, consistency and efficiency at once.\n");
+        let _ = writeln!(md, "| {} |", PARETO_HEADERS.join(" | "));
+        let _ = writeln!(md, "|---|---:|---:|---:|---:|---:|");
+        for row in pareto {
+            let row: Vec<String> = row.iter().map(|c| md_cell(c)).collect();
+            let _ = writeln!(md, "| {} |", row.join(" | "));
+        }
+    }
+
+    let archive = archive_rows(result);
+    if !archive.is_empty() {
+        let _ = writeln!(md, "\n## MAP-Elites Archive\n");
+        let _ = writeln!(md, "Best team of each behaviour niche reached during the run.\n");
+        let _ = writeln!(md, "| {} |", ARCHIVE_HEADERS.join(" | "));
+        let _ = writeln!(md, "|---|---|---|---|---:|");
+        for row in archive {
+            let row: Vec<String> = row.iter().map(|c| md_cell(c)).collect();
+            let _ = writeln!(md, "| {} |", row.join(" | "));
+        }
+    }
+
+    let _ = writeln!(md, "\n## Winning Team: {}\n", result.best_team.name);
+    let _ = writeln!(md, "| Agent | Strategy | Temperature |\n|---|---|---:|");
+    for agent in &result.best_team.agents {
+        let g = &agent.genotype;
+        let _ = writeln!(
+            md,
+            "| {} | {} | {:.2} |",
+            md_cell(&g.name),
+            g.strategy,
+            g.temperature
+        );
+    }
+
+    let _ = writeln!(md, "\n## Judge Critiques");
+    for (persona, text) in judge_critiques(result) {
+        let _ = writeln!(md, "\n### {persona}\n\n{}", text.trim());
+    }
+
+    let _ = writeln!(md, "\n## Agent Answers");
+    for output in &result.best_output.agent_outputs {
+        let _ = writeln!(
+            md,
+            "\n### {} ({})\n\n{}",
+            output.genotype_name,
+            output.strategy,
+            output.content.trim()
+        );
+    }
+
+    let _ = writeln!(md, "\n## Synthesis\n\n{}", result.synthesis.trim());
+    md
+}
+
+// HTML
+
+fn esc(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;max-width:960px;margin:2em auto;padding:0 1em;color:#222}\
+h1{border-bottom:2px solid #2a9d8f}h2{margin-top:1.8em;color:#264653}\
+table{border-collapse:collapse;margin:.5em 0}td,th{border:1px solid #ccc;padding:.3em .7em}\
+th{background:#f4f4f4}td.n{text-align:right}\
+.text{white-space:pre-wrap;background:#fafafa;border-left:3px solid #2a9d8f;padding:.6em 1em}\
+blockquote{white-space:pre-wrap;margin:0;padding:.6em 1em;background:#f4f4f4}";
+
+/// Inline SVG line chart of best (solid) and mean (dashed) scores.
+fn svg_trajectory(points: &[(usize, f64, f64)]) -> String {
+    const W: f64 = 600.0;
+    const H: f64 = 200.0;
+    const PAD: f64 = 30.0;
+    let x = |i: usize| {
+        if points.len() < 2 {
+            W / 2.0
+        } else {
+            PAD + i as f64 * (W - 2.0 * PAD) / (points.len() - 1) as f64
+        }
+    };
+    let y = |score: f64| H - PAD - score.clamp(0.0, 10.0) / 10.0 * (H - 2.0 * PAD);
+    let line = |pick: fn(&(usize, f64, f64)) -> f64| -> String {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("{:.1},{:.1}", x(i), y(pick(p))))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{W}\" height=\"{H}\" viewBox=\"0 0 {W} {H}\">"
+    );
+    for score in [0.0, 5.0, 10.0] {
+        let _ = write!(
+            svg,
+            "<line x1=\"{PAD}\" x2=\"{}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#ddd\"/>\
+             <text x=\"4\" y=\"{:.1}\" font-size=\"11\">{score}</text>",
+            W - PAD,
+            y(score) + 4.0,
+            y = y(score),
+        );
+    }
+    let _ = write!(
+        svg,
+        "<polyline fill=\"none\" stroke=\"#2a9d8f\" stroke-width=\"2\" points=\"{}\"/>\
+         <polyline fill=\"none\" stroke=\"#e76f51\" stroke-width=\"2\" stroke-dasharray=\"5,4\" points=\"{}\"/>",
+        line(|p| p.1),
+        line(|p| p.2),
+    );
+    for (i, (gen, best, _)) in points.iter().enumerate() {
+        let _ = write!(
+            svg,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"#2a9d8f\"><title>Gen {gen}: {best:.2}</title></circle>\
+             <text x=\"{:.1}\" y=\"{}\" font-size=\"11\" text-anchor=\"middle\">{gen}</text>",
+            x(i),
+            y(*best),
+            x(i),
+            H - 8.0,
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn render_html(problem: &str, result: &EvolutionResult) -> String {
+    let mut h = String::new();
+    let _ = write!(
+        h,
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <title>EMAS Run Report</title><style>{STYLE}</style></head><body>\n"
+    );
+    let _ = writeln!(h, "<h1>EMAS Run Report</h1>\n<h2>Problem</h2>");
+    let _ = writeln!(h, "<blockquote>{}</blockquote>\n<table>", esc(problem));
+    for (label, value) in summary_rows(result) {
+        let _ = writeln!(h, "<tr><th>{label}</th><td>{}</td></tr>", esc(&value));
+    }
+    let _ = writeln!(h, "</table>");
+
+    let points = trajectory(result);
+    let _ = writeln!(h, "<h2>Fitness Trajectory</h2>");
+    let _ = writeln!(
+        h,
+        "<p>Best score per generation (solid) and population mean (dashed).</p>\n{}",
+        svg_trajectory(&points)
+    );
+    let _ = writeln!(h, "<table><tr><th>Generation</th><th>Best</th><th>Mean</th></tr>");
+    for (gen, best, mean) in &points {
+        let _ = writeln!(
+            h,
+            "<tr><td class=\"n\">{gen}</td><td class=\"n\">{best:.2}</td><td class=\"n\">{mean:.2}</td></tr>"
+        );
+    }
+    let _ = writeln!(h, "</table>");
+
+    let _ = writeln!(h, "<h2>Scores per Generation</h2>");
+    for (i, scores) in result.generation_scores.iter().enumerate() {
+        let _ = writeln!(h, "<h3>Generation {}</h3>\n<table><tr>", i + 1);
+        for header in SCORE_HEADERS {
+            let _ = write!(h, "<th>{header}</th>");
+        }
+        let _ = writeln!(h, "</tr>");
+        for s in scores {
+            let cells = score_row(s);
+            let _ = write!(h, "<tr><td>{}</td>", esc(&cells[0]));
+            for cell in &cells[1..] {
+                let _ = write!(h, "<td class=\"n\">{cell}</td>");
+            }
+            let _ = writeln!(h, "</tr>");
+        }
+        let _ = writeln!(h, "</table>");
+    }
+
+    let pareto = pareto_rows(result);
+    if !pareto.is_empty() {
+        let _ = writeln!(
+            h,
+            "<h2>Pareto Front</h2>\n<p>Teams no other team beat on quality, consistency and efficiency at once.</p>\n<table><tr>"
+        );
+        for header in PARETO_HEADERS {
+            let _ = write!(h, "<th>{header}</th>");
+        }
+        let _ = writeln!(h, "</tr>");
+        for row in pareto {
+            let _ = write!(h, "<tr><td>{}</td>", esc(&row[0]));
+            for cell in &row[1..] {
+                let _ = write!(h, "<td class=\"n\">{cell}</td>");
+            }
+            let _ = writeln!(h, "</tr>");
+        }
+        let _ = writeln!(h, "</table>");
+    }
+
+    let archive = archive_rows(result);
+    if !archive.is_empty() {
+        let _ = writeln!(
+            h,
+            "<h2>MAP-Elites Archive</h2>\n<p>Best team of each behaviour niche reached during the run.</p>\n<table><tr>"
+        );
+        for header in ARCHIVE_HEADERS {
+            let _ = write!(h, "<th>{header}</th>");
+        }
+        let _ = writeln!(h, "</tr>");
+        for row in archive {
+            let _ = write!(h, "<tr>");
+            for cell in &row[..4] {
+                let _ = write!(h, "<td>{}</td>", esc(cell));
+            }
+            let _ = writeln!(h, "<td class=\"n\">{}</td></tr>", row[4]);
+        }
+        let _ = writeln!(h, "</table>");
+    }
+
+    let _ = writeln!(
+        h,
+        "<h2>Winning Team: {}</h2>\n<table><tr><th>Agent</th><th>Strategy</th><th>Temperature</th></tr>",
+        esc(&result.best_team.name)
+    );
+    for agent in &result.best_team.agents {
+        let g = &agent.genotype;
+        let _ = writeln!(
+            h,
+            "<tr><td>{}</td><td>{}</td><td class=\"n\">{:.2}</td></tr>",
+            esc(&g.name),
+            esc(&g.strategy.to_string()),
+            g.temperature
+        );
+    }
+    let _ = writeln!(h, "</table>");
+
+    let _ = writeln!(h, "<h2>Judge Critiques</h2>");
+    for (persona, text) in judge_critiques(result) {
+        let _ = writeln!(
+            h,
+            "<h3>{}</h3>\n<div class=\"text\">{}</div>",
+            esc(persona),
+            esc(text.trim())
+        );
+    }
+
+    let _ = writeln!(h, "<h2>Agent Answers</h2>");
+    for output in &result.best_output.agent_outputs {
+        let _ = writeln!(
+            h,
+            "<h3>{} ({})</h3>\n<div class=\"text\">{}</div>",
+            esc(&output.genotype_name),
+            esc(&output.strategy.to_string()),
+            esc(output.content.trim())
+        );
+    }
+
+    let _ = writeln!(
+        h,
+        "<h2>Synthesis</h2>\n<div class=\"text\">{}</div>\n</body></html>",
+        esc(result.synthesis.trim())
+    );
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::{Arena, RunControl};
+    use crate::config::{Config, Provider};
+    use crate::orchestrator::JudgeCritique;
+
+    async fn mock_result() -> EvolutionResult {
+        let config = Config {
+            provider: Provider::Mock,
+            model: "mock".into(),
+            judge_provider: Provider::Mock,
+            judge_model: "mock".into(),
+            population_size: 3,
+            max_generations: 2,
+            fitness_threshold: 10.0,
+            ..Config::default()
+        };
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        Arena::new(config)
+            .unwrap()
+            .run_with_progress("Is 7919 prime?", tx, &RunControl::default())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn markdown_report_covers_every_section() {
+        let mut result = mock_result().await;
+        result.best_score.critiques = vec![
+            JudgeCritique {
+                persona: "Strict Logician".into(),
+                text: "Sound proof.\n\n[Pragmatist] is quoted, not a judge.".into(),
+            },
+            JudgeCritique {
+                persona: "Pragmatist".into(),
+                text: "Too long.".into(),
+            },
+        ];
+
+        let md = render("Is 7919 prime?", &result, ReportFormat::Markdown);
+        for section in [
+            "# EMAS Run Report",
+            "## Problem",
+            "## Fitness Trajectory",
+            "### Generation 2",
+            "## MAP-Elites Archive",
+            "## Winning Team",
+            "## Judge Critiques",
+            "## Agent Answers",
+            "## Synthesis",
+        ] {
+            assert!(md.contains(section), "missing {section}");
+        }
+        // A critique quoting a persona tag stays one critique.
+        assert_eq!(md.matches("\n### Strict Logician\n").count(), 1);
+        assert_eq!(md.matches("\n### Pragmatist\n").count(), 1);
+        assert!(md.contains("[Pragmatist] is quoted, not a judge."));
+        assert_eq!(ReportFormat::from_path(Path::new("run.md")), ReportFormat::Markdown);
+    }
+
+    #[tokio::test]
+    async fn html_report_escapes_model_output() {
+        let payload = "<script>alert('x')</script>";
+        let mut result = mock_result().await;
+        result.synthesis = payload.into();
+        result.best_output.agent_outputs[0].content = payload.into();
+        result.best_score.critiques = vec![JudgeCritique {
+            persona: payload.into(),
+            text: payload.into(),
+        }];
+
+        let html = render("<b>Is 7919 prime?</b>", &result, ReportFormat::Html);
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("<b>Is"));
+        assert_eq!(html.matches("&lt;script&gt;").count(), 4);
+        for section in ["<h2>Problem</h2>", "<h2>Judge Critiques</h2>", "<h2>Synthesis</h2>"] {
+            assert!(html.contains(section), "missing {section}");
+        }
+        assert!(html.trim_end().ends_with("</html>"));
+        assert_eq!(ReportFormat::from_path(Path::new("run.HTML")), ReportFormat::Html);
+    }
+}
@@ -0,0 +1,112 @@
+use clap::ValueEnum;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Tournament size when none is configured.
+pub const DEFAULT_TOURNAMENT_SIZE: usize = 3;
+
+/// How crossover parents are drawn from a scored generation.
+///
+/// Elites always survive unchanged; the strategy only decides which teams
+/// breed the rest of the next generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectionStrategy {
+    /// Uniformly among the elites
+    #[default]
+    Truncation,
+    /// Best of k teams drawn at random from the whole generation
+    Tournament,
+    /// Fitness-proportionate over the whole generation
+    Roulette,
+    /// Linear rank: the best team is n times as likely as the worst
+    Rank,
+    /// NSGA-II: elites and tournaments ranked by Pareto front over quality,
+    /// consistency and efficiency, then crowding distance
+    Nsga2,
+}
+
+impl std::fmt::Display for SelectionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectionStrategy::Truncation => write!(f, "truncation"),
+            SelectionStrategy::Tournament => write!(f, "tournament"),
+            SelectionStrategy::Roulette => write!(f, "roulette"),
+            SelectionStrategy::Rank => write!(f, "rank"),
+            SelectionStrategy::Nsga2 => write!(f, "nsga2"),
+        }
+    }
+}
+
+impl SelectionStrategy {
+    /// Index of one parent in `ranked`, the generation's scores sorted best
+    /// first (by crowded comparison for [`Self::Nsga2`]). The first `elites`
+    /// entries are the surviving elites; `tournament_size` only matters for
+    /// the tournament strategies.
+    pub fn pick(
+        self,
+        ranked: &[f64],
+        elites: usize,
+        tournament_size: usize,
+        rng: &mut impl Rng,
+    ) -> usize {
+        let n = ranked.len();
+        match self {
+            SelectionStrategy::Truncation => rng.gen_range(0..elites.clamp(1, n)),
+            // Sorted best first, so the lowest index drawn wins.
+            SelectionStrategy::Tournament | SelectionStrategy::Nsga2 => (0..tournament_size.max(1))
+                .map(|_| rng.gen_range(0..n))
+                .min()
+                .unwrap_or(0),
+            SelectionStrategy::Roulette => {
+                weighted(ranked.iter().map(|score| score.max(0.0)), n, rng)
+            }
+            SelectionStrategy::Rank => weighted((0..n).map(|i| (n - i) as f64), n, rng),
+        }
+    }
+}
+
+/// Draws an index by weight, uniformly when no weight is positive.
+fn weighted(weights: impl Iterator<Item = f64>, n: usize, rng: &mut impl Rng) -> usize {
+    match WeightedIndex::new(weights) {
+        Ok(dist) => dist.sample(rng),
+        Err(_) => rng.gen_range(0..n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn strategies_favour_fitter_teams_to_different_degrees() {
+        let ranked = [9.0, 6.0, 3.0, 0.0];
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        let mut counts = |strategy: SelectionStrategy, k: usize| {
+            let mut counts = [0usize; 4];
+            for _ in 0..4000 {
+                counts[strategy.pick(&ranked, 2, k, &mut rng)] += 1;
+            }
+            counts
+        };
+
+        let truncation = counts(SelectionStrategy::Truncation, 0);
+        assert_eq!(truncation[2] + truncation[3], 0);
+
+        // Zero fitness is never picked by roulette; rank still gives it 1/10.
+        let roulette = counts(SelectionStrategy::Roulette, 0);
+        assert_eq!(roulette[3], 0);
+        assert!(roulette[0] > roulette[1] && roulette[1] > roulette[2]);
+        let rank = counts(SelectionStrategy::Rank, 0);
+        assert!(rank[3] > 250 && rank[3] < 550);
+
+        // Larger tournaments pick the best team more often.
+        let binary = counts(SelectionStrategy::Tournament, 2);
+        let quaternary = counts(SelectionStrategy::Tournament, 4);
+        assert!(binary[3] > 0);
+        assert!(quaternary[0] > binary[0]);
+    }
+}
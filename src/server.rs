@@ -0,0 +1,775 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{info, warn};
+
+use crate::arena::{Arena, ArenaEvent, EvolutionResult, RunControl};
+use crate::config::{parse_duration, Config};
+use crate::history::RunStore;
+use crate::llm::RateLimiter;
+use crate::selection::SelectionStrategy;
+
+/// Buffered events per SSE subscriber; slower clients skip ahead.
+const EVENT_BUFFER: usize = 1024;
+
+/// Per-job settings a client may change. Provider, endpoints, API keys and
+/// cassettes stay as `emas serve` was started with.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobOverrides {
+    pub model: Option<String>,
+    pub judge_model: Option<String>,
+    pub population: Option<usize>,
+    pub team_size: Option<usize>,
+    pub generations: Option<usize>,
+    pub threshold: Option<f64>,
+    pub mutation_rate: Option<f64>,
+    pub elite_fraction: Option<f64>,
+    pub selection: Option<SelectionStrategy>,
+    pub tournament_size: Option<usize>,
+    pub islands: Option<usize>,
+    pub migration_interval: Option<usize>,
+    pub migrants: Option<usize>,
+    pub island_mutation_rates: Option<Vec<f64>>,
+    pub fitness_sharing: Option<bool>,
+    pub species_threshold: Option<f64>,
+    pub archive_parent_rate: Option<f64>,
+    pub quality_weight: Option<f64>,
+    pub consistency_weight: Option<f64>,
+    pub efficiency_weight: Option<f64>,
+    pub max_tokens: Option<u32>,
+    pub max_cost: Option<f64>,
+    pub max_total_tokens: Option<u64>,
+    /// e.g. `"90s"`, `"10m"` or `"1h"`.
+    pub max_duration: Option<String>,
+    pub seed: Option<u64>,
+}
+
+impl JobOverrides {
+    /// `base` with these overrides applied and validated.
+    pub fn apply(&self, base: &Config) -> Result<Config> {
+        let mut config = base.clone();
+        if let Some(model) = &self.model {
+            config.model = model.clone();
+        }
+        if let Some(model) = &self.judge_model {
+            config.judge_model = model.clone();
+        }
+        if self.population.is_some() || self.elite_fraction.is_some() {
+            config.population_size = self.population.unwrap_or(config.population_size);
+            config.elite_fraction = self.elite_fraction.unwrap_or(config.elite_fraction);
+            config.elite_count =
+                Config::elite_count_for(config.population_size, config.elite_fraction);
+        }
+        config.selection = self.selection.unwrap_or(config.selection);
+        config.tournament_size = self.tournament_size.unwrap_or(config.tournament_size);
+        config.islands = self.islands.unwrap_or(config.islands);
+        config.migration_interval = self.migration_interval.unwrap_or(config.migration_interval);
+        config.migrants = self.migrants.unwrap_or(config.migrants);
+        if let Some(rates) = &self.island_mutation_rates {
+            config.island_mutation_rates = rates.clone();
+        }
+        config.fitness_sharing = self.fitness_sharing.unwrap_or(config.fitness_sharing);
+        config.species_threshold = self.species_threshold.unwrap_or(config.species_threshold);
+        config.archive_parent_rate = self.archive_parent_rate.unwrap_or(config.archive_parent_rate);
+        config.team_size = self.team_size.unwrap_or(config.team_size);
+        config.max_generations = self.generations.unwrap_or(config.max_generations);
+        config.fitness_threshold = self.threshold.unwrap_or(config.fitness_threshold);
+        config.mutation_rate = self.mutation_rate.unwrap_or(config.mutation_rate);
+        config.quality_weight = self.quality_weight.unwrap_or(config.quality_weight);
+        config.consistency_weight = self.consistency_weight.unwrap_or(config.consistency_weight);
+        config.efficiency_weight = self.efficiency_weight.unwrap_or(config.efficiency_weight);
+        config.max_tokens = self.max_tokens.unwrap_or(config.max_tokens);
+        config.max_cost = self.max_cost.or(config.max_cost);
+        config.max_total_tokens = self.max_total_tokens.or(config.max_total_tokens);
+        if let Some(duration) = &self.max_duration {
+            config.max_duration = Some(parse_duration(duration)?);
+        }
+        config.seed = self.seed.or(config.seed);
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Body of `POST /jobs`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobRequest {
+    pub problem: String,
+    #[serde(default)]
+    pub config: JobOverrides,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// An arena event as sent over SSE: the event name and its JSON payload.
+#[derive(Debug, Clone)]
+struct JobEvent {
+    name: String,
+    data: String,
+}
+
+impl JobEvent {
+    fn from_arena(event: &ArenaEvent) -> Self {
+        let value = serde_json::to_value(event).unwrap_or_default();
+        Self {
+            name: value["event"].as_str().unwrap_or("unknown").to_string(),
+            data: value
+                .get("data")
+                .unwrap_or(&serde_json::Value::Null)
+                .to_string(),
+        }
+    }
+
+    fn into_sse(self) -> Event {
+        Event::default().event(self.name).data(self.data)
+    }
+}
+
+struct Job {
+    id: String,
+    problem: String,
+    config: Config,
+    status: JobStatus,
+    created_at: u64,
+    generation: usize,
+    best_score: Option<f64>,
+    total_tokens: u64,
+    total_cost: f64,
+    error: Option<String>,
+    run_id: Option<String>,
+    result: Option<EvolutionResult>,
+    /// Everything sent so far, replayed to late SSE subscribers.
+    events: Vec<JobEvent>,
+    /// Dropped when the job finishes, which ends every SSE stream.
+    live: Option<broadcast::Sender<JobEvent>>,
+    abort: Option<tokio::task::AbortHandle>,
+}
+
+impl Job {
+    fn push(&mut self, event: JobEvent) {
+        if let Some(live) = &self.live {
+            let _ = live.send(event.clone());
+        }
+        self.events.push(event);
+    }
+
+    fn finish(&mut self, status: JobStatus) {
+        self.status = status;
+        self.abort = None;
+        self.live = None;
+    }
+
+    fn summary(&self) -> JobSummary {
+        JobSummary {
+            id: self.id.clone(),
+            status: self.status,
+            problem: self.problem.clone(),
+            created_at: self.created_at,
+            generation: self.generation,
+            max_generations: self.config.max_generations,
+            best_score: self.best_score,
+            total_tokens: self.total_tokens,
+            total_cost: self.total_cost,
+            seed: self.config.seed,
+            error: self.error.clone(),
+            run_id: self.run_id.clone(),
+        }
+    }
+}
+
+/// Job state returned by `GET /jobs` and `GET /jobs/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSummary {
+    pub id: String,
+    pub status: JobStatus,
+    pub problem: String,
+    /// Unix seconds when the job was submitted.
+    pub created_at: u64,
+    /// Last generation started (1-based), 0 before the first.
+    pub generation: usize,
+    pub max_generations: usize,
+    pub best_score: Option<f64>,
+    pub total_tokens: u64,
+    pub total_cost: f64,
+    pub seed: Option<u64>,
+    pub error: Option<String>,
+    /// Id in `emas history` once the job has completed.
+    pub run_id: Option<String>,
+}
+
+/// Jobs submitted to `emas serve`, run by a fixed pool of workers from a
+/// bounded queue.
+#[derive(Clone)]
+pub struct JobQueue {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    base: Config,
+    /// Shared by every job: they all call the same provider, so they draw on
+    /// one set of `--max-concurrency`, `--rpm` and `--tpm` budgets.
+    limiter: Arc<RateLimiter>,
+    jobs: Mutex<Vec<Job>>,
+    queue: mpsc::Sender<String>,
+    /// Finished jobs kept in memory; older ones are dropped.
+    keep: usize,
+    store: Option<RunStore>,
+}
+
+impl JobQueue {
+    /// Spawns `workers` workers. At most `capacity` jobs wait in the queue;
+    /// more are rejected. Only the last `keep` finished jobs stay listed.
+    /// Finished runs are saved to `store` if given.
+    pub fn start(
+        base: Config,
+        workers: usize,
+        capacity: usize,
+        keep: usize,
+        store: Option<RunStore>,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let queue = Self {
+            inner: Arc::new(Inner {
+                limiter: Arc::new(RateLimiter::new(base.max_concurrency, base.rpm, base.tpm)),
+                base,
+                jobs: Mutex::new(Vec::new()),
+                queue: tx,
+                keep,
+                store,
+            }),
+        };
+        let rx = Arc::new(tokio::sync::Mutex::new(rx));
+        for _ in 0..workers.max(1) {
+            let queue = queue.clone();
+            let rx = rx.clone();
+            tokio::spawn(async move {
+                loop {
+                    let next = rx.lock().await.recv().await;
+                    match next {
+                        Some(id) => queue.run_job(&id).await,
+                        None => return,
+                    }
+                }
+            });
+        }
+        queue
+    }
+
+    fn with_job<T>(&self, id: &str, f: impl FnOnce(&mut Job) -> T) -> Option<T> {
+        let mut jobs = self.inner.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.iter_mut().find(|job| job.id == id).map(f)
+    }
+
+    pub fn submit(&self, request: JobRequest) -> Result<JobSummary, ApiError> {
+        if request.problem.trim().is_empty() {
+            return Err(ApiError::bad_request("Problem cannot be empty"));
+        }
+        let mut config = request
+            .config
+            .apply(&self.inner.base)
+            .map_err(|e| ApiError::bad_request(format!("{e:#}")))?;
+        // Concurrent jobs would overwrite each other's checkpoint.
+        config.checkpoint_path = None;
+
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let job = Job {
+            id: id.clone(),
+            problem: request.problem,
+            config,
+            status: JobStatus::Queued,
+            created_at: unix_now(),
+            generation: 0,
+            best_score: None,
+            total_tokens: 0,
+            total_cost: 0.0,
+            error: None,
+            run_id: None,
+            result: None,
+            events: Vec::new(),
+            live: Some(broadcast::channel(EVENT_BUFFER).0),
+            abort: None,
+        };
+        let summary = job.summary();
+        // Hold the lock so a worker can't pick the id up before it's listed.
+        let mut jobs = self.inner.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        self.inner
+            .queue
+            .try_send(id)
+            .map_err(|_| ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "Job queue is full"))?;
+        drop_oldest_finished(&mut jobs, self.inner.keep);
+        jobs.push(job);
+        info!(job = %summary.id, "Job queued");
+        Ok(summary)
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<JobSummary, ApiError> {
+        self.with_job(id, |job| {
+            if job.status.is_finished() {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    format!("Job {} already {}", job.id, status_name(job.status)),
+                ));
+            }
+            if let Some(abort) = job.abort.take() {
+                abort.abort();
+            }
+            job.push(JobEvent {
+                name: "cancelled".into(),
+                data: "null".into(),
+            });
+            job.finish(JobStatus::Cancelled);
+            info!(job = %job.id, "Job cancelled");
+            Ok(job.summary())
+        })
+        .unwrap_or_else(|| Err(ApiError::not_found(id)))
+    }
+
+    async fn run_job(&self, id: &str) {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let limiter = self.inner.limiter.clone();
+        // Spawn and store the abort handle under the same lock that marks the
+        // job running, so a cancel can never find it running but unstoppable.
+        let Some(task) = self
+            .with_job(id, |job| {
+                (job.status == JobStatus::Queued).then(|| {
+                    let (problem, config) = (job.problem.clone(), job.config.clone());
+                    let task = tokio::spawn(async move {
+                        let arena = Arena::with_limiter(config, limiter)?;
+                        let result = arena
+                            .run_with_progress(&problem, tx, &RunControl::default())
+                            .await?;
+                        Ok::<_, anyhow::Error>((result, arena.config))
+                    });
+                    job.status = JobStatus::Running;
+                    job.abort = Some(task.abort_handle());
+                    task
+                })
+            })
+            .flatten()
+        else {
+            return;
+        };
+        info!(job = %id, "Job started");
+
+        while let Some(event) = rx.recv().await {
+            self.with_job(id, |job| {
+                match &event {
+                    ArenaEvent::GenerationStarted { gen, .. } => job.generation = *gen,
+                    ArenaEvent::GenerationComplete { best_score, .. } => {
+                        job.best_score = Some(job.best_score.unwrap_or(0.0).max(*best_score));
+                    }
+                    ArenaEvent::TokenUpdate {
+                        total_tokens,
+                        total_cost,
+                        ..
+                    } => {
+                        job.total_tokens = *total_tokens;
+                        job.total_cost = *total_cost;
+                    }
+                    _ => {}
+                }
+                job.push(JobEvent::from_arena(&event));
+            });
+        }
+
+        match task.await {
+            Ok(Ok((result, config))) => {
+                let run_id = self.inner.store.as_ref().and_then(|store| {
+                    match store.save(&self.problem_of(id), &config, &result) {
+                        Ok(record) => Some(record.id),
+                        Err(e) => {
+                            warn!(job = %id, "Run not saved to history: {e:#}");
+                            None
+                        }
+                    }
+                });
+                self.with_job(id, |job| {
+                    if job.status.is_finished() {
+                        return;
+                    }
+                    job.best_score = Some(result.best_score.total);
+                    job.total_tokens = result.total_tokens;
+                    job.total_cost = result.total_cost;
+                    job.run_id = run_id;
+                    job.push(JobEvent::from_arena(&ArenaEvent::Completed(result.clone())));
+                    job.result = Some(result);
+                    job.finish(JobStatus::Completed);
+                });
+                info!(job = %id, "Job completed");
+            }
+            Ok(Err(e)) => self.fail(id, format!("{e:#}")),
+            Err(e) if e.is_cancelled() => {}
+            Err(e) => self.fail(id, format!("job panicked: {e}")),
+        }
+    }
+
+    fn problem_of(&self, id: &str) -> String {
+        self.with_job(id, |job| job.problem.clone())
+            .unwrap_or_default()
+    }
+
+    fn fail(&self, id: &str, error: String) {
+        warn!(job = %id, "Job failed: {error}");
+        self.with_job(id, |job| {
+            if job.status.is_finished() {
+                return;
+            }
+            job.push(JobEvent::from_arena(&ArenaEvent::Error(error.clone())));
+            job.error = Some(error);
+            job.finish(JobStatus::Failed);
+        });
+    }
+}
+
+/// Removes the oldest finished jobs until at most `keep` are left. Jobs are
+/// listed in order of submission.
+fn drop_oldest_finished(jobs: &mut Vec<Job>, keep: usize) {
+    let mut excess = jobs
+        .iter()
+        .filter(|job| job.status.is_finished())
+        .count()
+        .saturating_sub(keep);
+    jobs.retain(|job| {
+        if excess > 0 && job.status.is_finished() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+fn status_name(status: JobStatus) -> &'static str {
+    match status {
+        JobStatus::Queued => "queued",
+        JobStatus::Running => "running",
+        JobStatus::Completed => "completed",
+        JobStatus::Failed => "failed",
+        JobStatus::Cancelled => "cancelled",
+    }
+}
+
+/// An HTTP error with a `{"error": "..."}` body.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    fn not_found(id: &str) -> Self {
+        Self::new(StatusCode::NOT_FOUND, format!("No job with id '{id}'"))
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+/// Routes of the job API:
+///
+/// - `POST /jobs` submits `{"problem": ..., "config": {...}}`
+/// - `GET /jobs` lists jobs, `GET /jobs/{id}` shows one
+/// - `GET /jobs/{id}/events` streams its arena events over SSE
+/// - `GET /jobs/{id}/result` returns the `EvolutionResult` once completed
+/// - `DELETE /jobs/{id}` cancels a queued or running job
+pub fn router(queue: JobQueue) -> Router {
+    Router::new()
+        .route("/jobs", get(list_jobs).post(create_job))
+        .route("/jobs/{id}", get(get_job).delete(cancel_job))
+        .route("/jobs/{id}/events", get(job_events))
+        .route("/jobs/{id}/result", get(job_result))
+        .with_state(queue)
+}
+
+async fn create_job(
+    State(queue): State<JobQueue>,
+    request: Result<Json<JobRequest>, JsonRejection>,
+) -> Result<(StatusCode, Json<JobSummary>), ApiError> {
+    let Json(request) = request.map_err(|e| ApiError::bad_request(e.body_text()))?;
+    Ok((StatusCode::ACCEPTED, Json(queue.submit(request)?)))
+}
+
+async fn list_jobs(State(queue): State<JobQueue>) -> Json<Vec<JobSummary>> {
+    let jobs = queue.inner.jobs.lock().unwrap_or_else(|e| e.into_inner());
+    Json(jobs.iter().map(Job::summary).collect())
+}
+
+async fn get_job(
+    State(queue): State<JobQueue>,
+    Path(id): Path<String>,
+) -> Result<Json<JobSummary>, ApiError> {
+    queue
+        .with_job(&id, |job| Json(job.summary()))
+        .ok_or_else(|| ApiError::not_found(&id))
+}
+
+async fn cancel_job(
+    State(queue): State<JobQueue>,
+    Path(id): Path<String>,
+) -> Result<Json<JobSummary>, ApiError> {
+    queue.cancel(&id).map(Json)
+}
+
+async fn job_result(
+    State(queue): State<JobQueue>,
+    Path(id): Path<String>,
+) -> Result<Json<EvolutionResult>, ApiError> {
+    queue
+        .with_job(&id, |job| match (&job.result, &job.error) {
+            (Some(result), _) => Ok(Json(result.clone())),
+            (None, Some(error)) => Err(ApiError::new(
+                StatusCode::CONFLICT,
+                format!("Job {} failed: {error}", job.id),
+            )),
+            (None, None) => Err(ApiError::new(
+                StatusCode::CONFLICT,
+                format!("Job {} is {}", job.id, status_name(job.status)),
+            )),
+        })
+        .unwrap_or_else(|| Err(ApiError::not_found(&id)))
+}
+
+async fn job_events(
+    State(queue): State<JobQueue>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let (past, live) = queue
+        .with_job(&id, |job| {
+            (
+                job.events.clone(),
+                job.live.as_ref().map(|tx| tx.subscribe()),
+            )
+        })
+        .ok_or_else(|| ApiError::not_found(&id))?;
+
+    let live = futures::stream::unfold(live, |rx| async move {
+        let mut rx = rx?;
+        loop {
+            match rx.recv().await {
+                Ok(event) => return Some((event, Some(rx))),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    let stream = futures::stream::iter(past)
+        .chain(live)
+        .map(|event| Ok(event.into_sse()));
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Settings for `emas serve`.
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub addr: SocketAddr,
+    pub workers: usize,
+    pub queue_size: usize,
+    pub keep_jobs: usize,
+}
+
+/// Serves the job API until the process is stopped. `base` is the config
+/// every job starts from.
+pub async fn serve(base: Config, options: ServeOptions) -> Result<()> {
+    if options.workers == 0 {
+        bail!("--workers must be at least 1");
+    }
+    let store = match RunStore::open_default() {
+        Ok(store) => Some(store),
+        Err(e) => {
+            warn!("Finished jobs won't be saved to history: {e:#}");
+            None
+        }
+    };
+    let queue = JobQueue::start(
+        base,
+        options.workers,
+        options.queue_size,
+        options.keep_jobs,
+        store,
+    );
+    let listener = tokio::net::TcpListener::bind(options.addr)
+        .await
+        .with_context(|| format!("binding {}", options.addr))?;
+    info!(
+        "EMAS job API listening on http://{} ({} worker(s), queue of {})",
+        listener.local_addr()?,
+        options.workers,
+        options.queue_size
+    );
+    axum::serve(listener, router(queue))
+        .await
+        .context("serving HTTP")
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Provider;
+
+    fn mock_base() -> Config {
+        Config {
+            provider: Provider::Mock,
+            model: "mock".into(),
+            judge_provider: Provider::Mock,
+            judge_model: "mock".into(),
+            population_size: 3,
+            max_generations: 3,
+            fitness_threshold: 10.0,
+            ..Config::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn submitted_job_runs_to_completion_over_http() {
+        let base = mock_base();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let queue = JobQueue::start(base, 1, 1, 16, None);
+        tokio::spawn(async move { axum::serve(listener, router(queue)).await });
+        let http = reqwest::Client::new();
+
+        let bad = http
+            .post(format!("{url}/jobs"))
+            .json(&serde_json::json!({"problem": "Is 7919 prime?", "config": {"population": 1}}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(bad.status(), StatusCode::BAD_REQUEST);
+
+        let overlong = format!("{}h", "9".repeat(400));
+        let bad = http
+            .post(format!("{url}/jobs"))
+            .json(&serde_json::json!({"problem": "Is 7919 prime?", "config": {"max_duration": overlong}}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(bad.status(), StatusCode::BAD_REQUEST);
+
+        let created: JobSummary = http
+            .post(format!("{url}/jobs"))
+            .json(&serde_json::json!({"problem": "Is 7919 prime?", "config": {"generations": 2, "seed": 7}}))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(created.max_generations, 2);
+
+        // The SSE stream ends with the result once the job is done.
+        let events = http
+            .get(format!("{url}/jobs/{}/events", created.id))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(events.contains("event: generation_complete"));
+        assert!(events.contains("event: completed"));
+
+        let job: JobSummary = http
+            .get(format!("{url}/jobs/{}", created.id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        let result: EvolutionResult = http
+            .get(format!("{url}/jobs/{}/result", created.id))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(result.seed, 7);
+        assert_eq!(result.generation_scores.len(), 2);
+
+        let cancel = http
+            .delete(format!("{url}/jobs/{}", created.id))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(cancel.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn only_the_last_finished_jobs_are_kept() {
+        let queue = JobQueue::start(mock_base(), 1, 4, 2, None);
+        let mut ids = Vec::new();
+        for _ in 0..4 {
+            let request = JobRequest {
+                problem: "Is 7919 prime?".into(),
+                config: JobOverrides::default(),
+            };
+            let id = queue.submit(request).unwrap().id;
+            while !queue.with_job(&id, |job| job.status.is_finished()).unwrap() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            ids.push(id);
+        }
+
+        // Submitting the fourth dropped the first: two finished jobs plus the newest.
+        let listed: Vec<String> = queue
+            .inner
+            .jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|job| job.id.clone())
+            .collect();
+        assert_eq!(listed, ids[1..]);
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use crate::genotype::{ReasoningStrategy, MUTATION_MODIFIERS};
+use crate::team::Team;
+
+/// Genotype distance below which two teams count as the same species.
+pub const DEFAULT_SPECIES_THRESHOLD: f64 = 0.3;
+
+const STRATEGY_WEIGHT: f64 = 0.5;
+const TEMPERATURE_WEIGHT: f64 = 0.25;
+const MODIFIER_WEIGHT: f64 = 0.25;
+
+/// Genotype distance between two teams in `[0, 1]`: a weighted mix of how
+/// far apart their strategy multisets are, how far their sorted agent
+/// temperatures are, and the Jaccard distance between the instruction
+/// modifiers their agents carry. Names, ids and judge feedback are ignored.
+pub fn team_distance(a: &Team, b: &Team) -> f64 {
+    STRATEGY_WEIGHT * strategy_distance(a, b)
+        + TEMPERATURE_WEIGHT * temperature_distance(a, b)
+        + MODIFIER_WEIGHT * modifier_distance(a, b)
+}
+
+/// Share of agents whose strategy has no counterpart in the other team.
+fn strategy_distance(a: &Team, b: &Team) -> f64 {
+    let total = a.agents.len() + b.agents.len();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut counts: HashMap<&ReasoningStrategy, i64> = HashMap::new();
+    for agent in &a.agents {
+        *counts.entry(&agent.genotype.strategy).or_default() += 1;
+    }
+    for agent in &b.agents {
+        *counts.entry(&agent.genotype.strategy).or_default() -= 1;
+    }
+    let unmatched: i64 = counts.values().map(|c| c.abs()).sum();
+    unmatched as f64 / total as f64
+}
+
+/// Mean gap between the teams' temperatures paired in sorted order, scaled
+/// by the widest temperature range (0.0-2.0). Unpaired agents count as the
+/// full range.
+fn temperature_distance(a: &Team, b: &Team) -> f64 {
+    let sorted = |team: &Team| {
+        let mut temps: Vec<f64> = team.agents.iter().map(|a| a.genotype.temperature).collect();
+        temps.sort_by(|x, y| x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal));
+        temps
+    };
+    let (ta, tb) = (sorted(a), sorted(b));
+    let longest = ta.len().max(tb.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    let paired: f64 = ta.iter().zip(&tb).map(|(x, y)| (x - y).abs() / 2.0).sum();
+    let unpaired = (longest - ta.len().min(tb.len())) as f64;
+    ((paired + unpaired) / longest as f64).min(1.0)
+}
+
+/// Jaccard distance between the sets of mutation modifiers present in each
+/// team's instructions; zero when neither team has any.
+fn modifier_distance(a: &Team, b: &Team) -> f64 {
+    let modifiers = |team: &Team| -> Vec<bool> {
+        MUTATION_MODIFIERS
+            .iter()
+            .map(|m| {
+                team.agents
+                    .iter()
+                    .any(|agent| agent.genotype.base_instruction.contains(m))
+            })
+            .collect()
+    };
+    let (ma, mb) = (modifiers(a), modifiers(b));
+    let union = ma.iter().zip(&mb).filter(|(x, y)| **x || **y).count();
+    if union == 0 {
+        return 0.0;
+    }
+    let shared = ma.iter().zip(&mb).filter(|(x, y)| **x && **y).count();
+    1.0 - shared as f64 / union as f64
+}
+
+/// Species of each team, in order. Teams are visited in the order given and
+/// join the first species whose founding team is within `threshold`;
+/// otherwise they found a new one. Species are numbered from 0 in order of
+/// foundation, so with teams sorted best first, species 0 holds the best.
+pub fn speciate(teams: &[&Team], threshold: f64) -> Vec<usize> {
+    let mut founders: Vec<&Team> = Vec::new();
+    teams
+        .iter()
+        .map(|team| {
+            match founders
+                .iter()
+                .position(|founder| team_distance(founder, team) <= threshold)
+            {
+                Some(species) => species,
+                None => {
+                    founders.push(team);
+                    founders.len() - 1
+                }
+            }
+        })
+        .collect()
+}
+
+/// Number of teams in each species, indexed by species.
+pub fn species_sizes(species: &[usize]) -> Vec<usize> {
+    let mut sizes = vec![0; species.iter().max().map_or(0, |s| s + 1)];
+    for &s in species {
+        sizes[s] += 1;
+    }
+    sizes
+}
+
+/// Species sizes as shown in progress output, e.g. `"3 (2/2/1)"`.
+pub fn summary(sizes: &[usize]) -> String {
+    let sizes: Vec<String> = sizes.iter().map(|n| n.to_string()).collect();
+    format!("{} ({})", sizes.len(), sizes.join("/"))
+}
+
+/// Scores divided by the size of each team's species, so a large cluster of
+/// near-identical teams no longer crowds out a small novel one.
+pub fn shared_fitness(scores: &[f64], species: &[usize]) -> Vec<f64> {
+    let sizes = species_sizes(species);
+    scores
+        .iter()
+        .zip(species)
+        .map(|(score, &s)| score / sizes[s] as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::Agent;
+    use crate::genotype::Genotype;
+    use uuid::Uuid;
+
+    fn team(name: &str, agents: &[(ReasoningStrategy, f64, &str)]) -> Team {
+        Team {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            agents: agents
+                .iter()
+                .map(|(strategy, temperature, extra)| {
+                    let mut genotype = Genotype::new(name, strategy.clone(), *temperature);
+                    genotype.base_instruction.push_str(extra);
+                    Agent::new(genotype)
+                })
+                .collect(),
+            generation: 0,
+            island: 0,
+        }
+    }
+
+    #[test]
+    fn near_clones_share_a_species_and_split_its_fitness() {
+        use ReasoningStrategy::*;
+        let modifier = MUTATION_MODIFIERS[0];
+        let a = team("A", &[(Logical, 0.5, ""), (Critical, 0.7, "")]);
+        let a2 = team("A2", &[(Critical, 0.6, ""), (Logical, 0.5, "")]);
+        let b = team("B", &[(Creative, 1.4, modifier), (DevilsAdvocate, 1.2, "")]);
+
+        assert_eq!(team_distance(&a, &a), 0.0);
+        assert!(team_distance(&a, &a2) < 0.05);
+        assert!(team_distance(&a, &b) > 0.8);
+        assert_eq!(team_distance(&a, &b), team_distance(&b, &a));
+
+        let species = speciate(&[&a, &a2, &b], DEFAULT_SPECIES_THRESHOLD);
+        assert_eq!(species, vec![0, 0, 1]);
+        assert_eq!(species_sizes(&species), vec![2, 1]);
+
+        // The novel team outranks the weaker clone once fitness is shared.
+        let shared = shared_fitness(&[8.0, 7.0, 5.0], &species);
+        assert_eq!(shared, vec![4.0, 3.5, 5.0]);
+    }
+}
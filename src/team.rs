@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use uuid::Uuid;
 
@@ -8,12 +9,18 @@ use crate::agent::{Agent, AgentOutput};
 use crate::config::Config;
 use crate::llm::LlmClient;
 
-#[derive(Debug, Clone)]
+/// Receives `(agent, text fragment)` while a team's agents stream.
+pub type AgentSink<'a> = dyn Fn(&Agent, &str) + Send + Sync + 'a;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Team {
     pub id: Uuid,
     pub name: String,
     pub agents: Vec<Agent>,
     pub generation: usize,
+    /// Sub-population the team belongs to; always 0 without `--islands`.
+    #[serde(default)]
+    pub island: usize,
 }
 
 impl Team {
@@ -22,13 +29,14 @@ impl Team {
         problem: &str,
         llm: &LlmClient,
         config: &Config,
+        on_delta: Option<&AgentSink<'_>>,
     ) -> Result<TeamOutput> {
         let (standard, red_team): (Vec<_>, Vec<_>) =
             self.agents.iter().partition(|a| !a.genotype.is_red_team);
 
         let standard_futures: Vec<_> = standard
             .iter()
-            .map(|agent| agent.execute(problem, llm, config))
+            .map(|agent| Self::execute_agent(agent, problem, llm, config, on_delta))
             .collect();
 
         let standard_results = join_all(standard_futures).await;
@@ -67,7 +75,7 @@ impl Team {
 
             let red_futures: Vec<_> = red_team
                 .iter()
-                .map(|agent| agent.execute(&red_prompt, llm, config))
+                .map(|agent| Self::execute_agent(agent, &red_prompt, llm, config, on_delta))
                 .collect();
 
             let red_results = join_all(red_futures).await;
@@ -84,6 +92,7 @@ impl Team {
         }
 
         let total_tokens: u32 = agent_outputs.iter().map(|o| o.tokens_used).sum();
+        let total_cost: f64 = agent_outputs.iter().map(|o| o.cost).sum();
         let total_elapsed: Duration = agent_outputs
             .iter()
             .map(|o| o.elapsed)
@@ -95,18 +104,36 @@ impl Team {
             team_name: self.name.clone(),
             agent_outputs,
             total_tokens,
+            total_cost,
             total_elapsed,
             warnings,
         })
     }
+
+    async fn execute_agent(
+        agent: &Agent,
+        problem: &str,
+        llm: &LlmClient,
+        config: &Config,
+        on_delta: Option<&AgentSink<'_>>,
+    ) -> Result<AgentOutput> {
+        match on_delta {
+            Some(sink) => {
+                let forward = move |delta: &str| sink(agent, delta);
+                agent.execute(problem, llm, config, Some(&forward)).await
+            }
+            None => agent.execute(problem, llm, config, None).await,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamOutput {
     pub team_id: Uuid,
     pub team_name: String,
     pub agent_outputs: Vec<AgentOutput>,
     pub total_tokens: u32,
+    pub total_cost: f64,
     pub total_elapsed: Duration,
     pub warnings: Vec<String>,
 }
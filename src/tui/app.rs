@@ -1,7 +1,14 @@
 use anyhow::{bail, Result};
+use uuid::Uuid;
 
-use crate::arena::{ArenaEvent, EvolutionResult, Phase, TeamDetail, TeamScore};
-use crate::config::{Cli, Config, Provider, SavedParams};
+use crate::arena::{
+    ArenaEvent, EvolutionResult, IslandScore, Phase, RunControl, TeamDetail, TeamScore,
+};
+use crate::config::{Config, EvolutionArgs, LlmArgs, ProblemArgs, Provider, SavedParams, TuiArgs};
+use crate::config_file;
+use crate::problem::{attach, Problem};
+use crate::report::write_report;
+use crate::species;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Screen {
@@ -113,6 +120,20 @@ pub const F_EFFICIENCY_W: usize = 14;
 
 pub const FIELD_COUNT: usize = 15;
 
+/// Text streamed so far by one team's agents in the current generation.
+#[derive(Debug, Clone, Default)]
+pub struct LiveTeam {
+    pub name: String,
+    pub agents: Vec<LiveAgent>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LiveAgent {
+    pub id: Uuid,
+    pub name: String,
+    pub text: String,
+}
+
 pub struct App {
     pub screen: Screen,
     pub fields: Vec<FormField>,
@@ -134,62 +155,91 @@ pub struct App {
     pub gen_started_at: Option<std::time::Instant>,
 
     pub total_tokens: u64,
+    pub total_cost: f64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
 
     pub phase: Phase,
 
     pub team_details: Vec<TeamDetail>,
+    /// Latest per-island results; empty without `--islands`.
+    pub islands: Vec<IslandScore>,
     pub selected_team: usize,
     pub show_team_detail: bool,
 
+    pub live_output: Vec<LiveTeam>,
+    pub live_team: usize,
+    pub live_scroll: u16,
+    pub show_live_output: bool,
+
     pub problem_text_cache: String,
+    /// Pauses or stops the run on the Running screen.
+    pub control: Option<RunControl>,
 
     pub result: Option<EvolutionResult>,
     pub scroll_offset: u16,
+    /// Outcome of the last report export, shown in the Results help line.
+    pub export_message: Option<String>,
+
+    /// Options passed to `emas tui` that have no form field.
+    launch_input: ProblemArgs,
+    launch_llm: LlmArgs,
+    launch_evolution: EvolutionArgs,
 
     pub should_quit: bool,
     pub start_requested: bool,
 }
 
 impl App {
-    pub fn new(cli: &Cli) -> Self {
-        let saved = SavedParams::load();
-
-        let provider_idx = match cli.provider.or(saved.provider) {
+    pub fn new(args: &TuiArgs) -> Self {
+        let (llm, evolution) = (&args.llm, &args.evolution);
+        // Form defaults follow the same files as `Config::from_cli`; errors
+        // there are reported when the run starts.
+        let saved = config_file::load(evolution.profile.as_deref())
+            .map(|files| files.values)
+            .unwrap_or_default()
+            .or(SavedParams::load().into());
+
+        let provider_idx = match llm.provider.or(saved.provider) {
             Some(Provider::Google) => 1,
+            Some(Provider::Anthropic) => 2,
+            Some(Provider::Mock) => 3,
             _ => 0,
         };
-        let judge_provider_idx = match cli.judge_provider.or(saved.judge_provider) {
+        let judge_provider_idx = match llm.judge_provider.or(saved.judge_provider) {
             None => 0,           // "Auto"
             Some(Provider::Openai) => 1,
             Some(Provider::Google) => 2,
+            Some(Provider::Anthropic) => 3,
+            Some(Provider::Mock) => 4,
         };
 
-        let population = cli.population.or(saved.population).unwrap_or(5);
-        let team_size = cli.team_size.or(saved.team_size).unwrap_or(3);
-        let generations = cli.generations.or(saved.generations).unwrap_or(10);
-        let threshold = cli.threshold.or(saved.threshold).unwrap_or(8.5);
-        let mutation_rate = cli.mutation_rate.or(saved.mutation_rate).unwrap_or(0.3);
-        let quality_weight = cli.quality_weight.or(saved.quality_weight).unwrap_or(0.50);
-        let consistency_weight = cli.consistency_weight.or(saved.consistency_weight).unwrap_or(0.30);
-        let efficiency_weight = cli.efficiency_weight.or(saved.efficiency_weight).unwrap_or(0.20);
+        let population = evolution.population.or(saved.population).unwrap_or(5);
+        let team_size = evolution.team_size.or(saved.team_size).unwrap_or(3);
+        let generations = evolution.generations.or(saved.generations).unwrap_or(10);
+        let threshold = evolution.threshold.or(saved.threshold).unwrap_or(8.5);
+        let mutation_rate = evolution.mutation_rate.or(saved.mutation_rate).unwrap_or(0.3);
+        let quality_weight = evolution.quality_weight.or(saved.quality_weight).unwrap_or(0.50);
+        let consistency_weight = evolution.consistency_weight.or(saved.consistency_weight).unwrap_or(0.30);
+        let efficiency_weight = evolution.efficiency_weight.or(saved.efficiency_weight).unwrap_or(0.20);
 
-        let model_str = cli.model.as_deref()
+        let model_str = llm.model.as_deref()
             .or(saved.model.as_deref())
             .unwrap_or("");
-        let api_url_str = cli.api_url.as_deref()
+        let api_url_str = llm.api_url.as_deref()
             .or(saved.api_url.as_deref())
             .unwrap_or("");
-        let judge_model_str = cli.judge_model.as_deref()
+        let judge_model_str = llm.judge_model.as_deref()
             .or(saved.judge_model.as_deref())
             .unwrap_or("");
 
         let fields = vec![
             FormField::text(
                 "Problem",
-                cli.problem.as_deref().unwrap_or(""),
+                args.input.problem.as_deref().unwrap_or(""),
                 "Describe the problem to solve...",
             ),
-            FormField::select("Provider", vec!["openai", "google"], provider_idx),
+            FormField::select("Provider", vec!["openai", "google", "anthropic", "mock"], provider_idx),
             FormField::text(
                 "Model",
                 model_str,
@@ -202,12 +252,12 @@ impl App {
             ),
             FormField::sensitive(
                 "API Key",
-                cli.api_key.as_deref().unwrap_or(""),
-                "$EMAS_API_KEY / $GOOGLE_API_KEY / $OPENAI_API_KEY",
+                llm.api_key.as_deref().unwrap_or(""),
+                "$EMAS_API_KEY / $GOOGLE_API_KEY / $OPENAI_API_KEY / $ANTHROPIC_API_KEY",
             ),
             FormField::select(
                 "Judge Provider",
-                vec!["auto", "openai", "google"],
+                vec!["auto", "openai", "google", "anthropic", "mock"],
                 judge_provider_idx,
             ),
             FormField::text(
@@ -245,14 +295,28 @@ impl App {
             gen_durations: Vec::new(),
             gen_started_at: None,
             total_tokens: 0,
+            total_cost: 0.0,
+            cache_hits: 0,
+            cache_misses: 0,
             phase: Phase::Initialising,
             team_details: Vec::new(),
+            islands: Vec::new(),
             selected_team: 0,
             show_team_detail: false,
+            live_output: Vec::new(),
+            live_team: 0,
+            live_scroll: 0,
+            show_live_output: false,
             problem_text_cache: String::new(),
+            control: None,
 
             result: None,
             scroll_offset: 0,
+            export_message: None,
+
+            launch_input: args.input.clone(),
+            launch_llm: args.llm.clone(),
+            launch_evolution: args.evolution.clone(),
 
             should_quit: false,
             start_requested: false,
@@ -284,24 +348,23 @@ impl App {
             .map_err(|_| anyhow::anyhow!("'{}' is not a valid integer for {}", v, self.fields[idx].label))
     }
 
-    pub fn build_config(&self) -> Result<(Config, String)> {
-        let problem = self.problem_text().to_string();
-        if problem.trim().is_empty() {
+    /// The run's config and the form's problem with any `--context` files
+    /// attached.
+    pub fn build_config(&self) -> Result<(Config, Problem)> {
+        if self.problem_text().trim().is_empty() {
             bail!("Problem cannot be empty");
         }
+        let problem = attach(
+            self.problem_text(),
+            &self.launch_input.context,
+            self.launch_input.max_context_bytes,
+        )?;
 
         let provider_str = self.field_val(F_PROVIDER);
-        let provider = match provider_str {
-            "google" => Some(Provider::Google),
-            _ => Some(Provider::Openai),
-        };
+        let provider = Some(Provider::parse_loose(provider_str).unwrap_or(Provider::Openai));
 
         let judge_provider_str = self.field_val(F_JUDGE_PROVIDER);
-        let judge_provider = match judge_provider_str {
-            "openai" => Some(Provider::Openai),
-            "google" => Some(Provider::Google),
-            _ => None,
-        };
+        let judge_provider = Provider::parse_loose(judge_provider_str);
 
         let model = {
             let v = &self.fields[F_MODEL].value;
@@ -320,31 +383,30 @@ impl App {
             if v.is_empty() { None } else { Some(v.clone()) }
         };
 
-        let cli = Cli {
-            problem: Some(problem.clone()),
-            tui: true,
+        // Options the form doesn't cover come from the `emas tui` command line.
+        let llm = LlmArgs {
+            provider,
+            model,
+            api_url,
+            api_key,
+            judge_model,
+            judge_provider,
+            ..self.launch_llm.clone()
+        };
+        let evolution = EvolutionArgs {
             population: Some(self.field_usize(F_POPULATION)?),
             team_size: Some(self.field_usize(F_TEAM_SIZE)?),
             generations: Some(self.field_usize(F_GENERATIONS)?),
             threshold: Some(self.field_f64(F_THRESHOLD)?),
             mutation_rate: Some(self.field_f64(F_MUTATION)?),
-            provider,
-            model,
-            api_url,
-            api_key,
-            max_tokens: None,
             quality_weight: Some(self.field_f64(F_QUALITY_W)?),
             consistency_weight: Some(self.field_f64(F_CONSISTENCY_W)?),
             efficiency_weight: Some(self.field_f64(F_EFFICIENCY_W)?),
-            judge_model,
-            judge_provider,
-            judge_api_url: None,
-            judge_api_key: None,
-            no_save: false,
-            reset_defaults: false,
+            ..self.launch_evolution.clone()
         };
 
-        let config = Config::from_cli(&cli)?;
+        let mut config = Config::from_cli(&llm, &evolution)?;
+        config.stream = true;
         Ok((config, problem))
     }
 
@@ -356,6 +418,8 @@ impl App {
                     self.gen_durations.push(started.elapsed().as_secs_f64());
                 }
                 self.gen_started_at = Some(std::time::Instant::now());
+                self.live_output.clear();
+                self.live_scroll = 0;
                 self.generation = gen;
                 self.max_generations = total;
                 self.status = format!("Generation {}/{}  |  executing teams...", gen, total);
@@ -365,8 +429,42 @@ impl App {
             ArenaEvent::PhaseChanged(phase) => {
                 self.phase = phase;
             }
-            ArenaEvent::TokenUpdate { total_tokens } => {
+            ArenaEvent::TokenUpdate {
+                total_tokens,
+                total_cost,
+                cache_hits,
+                cache_misses,
+            } => {
                 self.total_tokens = total_tokens;
+                self.total_cost = total_cost;
+                self.cache_hits = cache_hits;
+                self.cache_misses = cache_misses;
+            }
+            ArenaEvent::AgentChunk {
+                team,
+                agent_id,
+                agent,
+                delta,
+            } => {
+                let idx = match self.live_output.iter().position(|t| t.name == team) {
+                    Some(idx) => idx,
+                    None => {
+                        self.live_output.push(LiveTeam {
+                            name: team,
+                            agents: Vec::new(),
+                        });
+                        self.live_output.len() - 1
+                    }
+                };
+                let agents = &mut self.live_output[idx].agents;
+                match agents.iter_mut().find(|a| a.id == agent_id) {
+                    Some(live) => live.text.push_str(&delta),
+                    None => agents.push(LiveAgent {
+                        id: agent_id,
+                        name: agent,
+                        text: delta,
+                    }),
+                }
             }
             ArenaEvent::TeamDetails(details) => {
                 self.team_details = details;
@@ -379,6 +477,7 @@ impl App {
                 scores,
                 best_name,
                 best_score,
+                species,
             } => {
                 self.team_scores = scores;
                 self.best_name = best_name.clone();
@@ -388,10 +487,29 @@ impl App {
                     gen, self.max_generations, best_name, best_score
                 );
                 self.logs.push(format!(
-                    "[{}] Gen {} evaluated  |  Best: {} = {:.2}",
-                    now, gen, best_name, best_score
+                    "[{}] Gen {} evaluated  |  Best: {} = {:.2}  |  Species: {}",
+                    now,
+                    gen,
+                    best_name,
+                    best_score,
+                    species::summary(&species)
                 ));
             }
+            ArenaEvent::IslandScores { islands, .. } => {
+                self.islands = islands;
+            }
+            ArenaEvent::Migrated { gen, migrations } => {
+                for m in migrations {
+                    self.logs.push(format!(
+                        "[{}] Gen {}: {} migrated from island {} to {}",
+                        now,
+                        gen,
+                        m.team,
+                        m.from + 1,
+                        m.to + 1
+                    ));
+                }
+            }
             ArenaEvent::Evolving { kept, spawning } => {
                 self.logs.push(format!(
                     "[{}] Evolving: {} elites kept, {} children spawned",
@@ -408,6 +526,35 @@ impl App {
                     now, gen, score
                 ));
             }
+            ArenaEvent::BudgetExhausted { gen, budget, detail } => {
+                if let Some(started) = self.gen_started_at.take() {
+                    self.gen_durations.push(started.elapsed().as_secs_f64());
+                }
+                self.status = format!("Stopped in generation {}: {} budget exhausted", gen, budget);
+                self.logs.push(format!(
+                    "[{}] {} budget exhausted in gen {} ({})",
+                    now, budget, gen, detail
+                ));
+            }
+            ArenaEvent::Paused { gen } => {
+                self.status = format!("Paused in generation {}  |  p: resume", gen);
+                self.logs.push(format!("[{}] Paused in gen {}", now, gen));
+            }
+            ArenaEvent::Resumed => {
+                self.status = format!(
+                    "Generation {}/{}  |  resumed",
+                    self.generation, self.max_generations
+                );
+                self.logs.push(format!("[{}] Resumed", now));
+            }
+            ArenaEvent::Stopped { gen } => {
+                if let Some(started) = self.gen_started_at.take() {
+                    self.gen_durations.push(started.elapsed().as_secs_f64());
+                }
+                self.status = format!("Stopped in generation {}", gen);
+                self.logs
+                    .push(format!("[{}] Stopped early in gen {}", now, gen));
+            }
             ArenaEvent::SynthesisStarted => {
                 if let Some(started) = self.gen_started_at.take() {
                     self.gen_durations.push(started.elapsed().as_secs_f64());
@@ -416,11 +563,20 @@ impl App {
                 self.logs
                     .push(format!("[{}] Synthesising final response...", now));
             }
+            ArenaEvent::CheckpointSaved { gen, path } => {
+                self.logs.push(format!(
+                    "[{}] Checkpoint after gen {} saved to {}",
+                    now,
+                    gen,
+                    path.display()
+                ));
+            }
             ArenaEvent::Warning(msg) => {
                 self.logs.push(format!("[{}] Warning: {}", now, msg));
             }
             ArenaEvent::Completed(result) => {
                 self.result = Some(result);
+                self.control = None;
                 self.screen = Screen::Results;
                 self.scroll_offset = 0;
                 self.status = "Done".into();
@@ -430,6 +586,7 @@ impl App {
                 self.status = format!("Error: {}", msg);
                 self.logs.push(format!("[{}] Error: {}", now, msg));
                 self.error_message = Some(msg);
+                self.control = None;
                 self.screen = Screen::Setup;
             }
         }
@@ -459,6 +616,37 @@ impl App {
         Some(format_duration(eta_secs))
     }
 
+    /// Asks the run to hold at its next phase boundary, or lets it go on.
+    pub fn toggle_pause(&mut self) {
+        let Some(control) = &self.control else {
+            return;
+        };
+        if control.is_stopped() {
+            return;
+        }
+        if control.is_paused() {
+            control.resume();
+            self.status = "Resuming...".into();
+        } else {
+            control.pause();
+            self.status = "Pausing after the current phase...".into();
+        }
+    }
+
+    /// Ends evolution early; the best team so far is still synthesised.
+    pub fn stop_run(&mut self) {
+        if let Some(control) = &self.control {
+            control.stop();
+            self.status = "Stopping: the best team so far will be synthesised...".into();
+            self.logs
+                .push(format!("[{}] Stop requested", chrono_now()));
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.control.as_ref().is_some_and(|c| c.is_paused())
+    }
+
     pub fn reset_for_new_run(&mut self) {
         self.screen = Screen::Setup;
         self.generation = 0;
@@ -470,16 +658,58 @@ impl App {
         self.status = "Waiting...".into();
         self.result = None;
         self.scroll_offset = 0;
+        self.export_message = None;
         self.error_message = None;
         self.run_started_at = None;
         self.gen_durations.clear();
         self.gen_started_at = None;
         self.total_tokens = 0;
+        self.total_cost = 0.0;
+        self.cache_hits = 0;
+        self.cache_misses = 0;
         self.phase = Phase::Initialising;
         self.team_details.clear();
+        self.islands.clear();
         self.selected_team = 0;
         self.show_team_detail = false;
+        self.live_output.clear();
+        self.live_team = 0;
+        self.live_scroll = 0;
+        self.show_live_output = false;
         self.problem_text_cache.clear();
+        self.control = None;
+    }
+
+    /// Writes Markdown and HTML reports of the finished run to the working
+    /// directory, named after the run's seed.
+    pub fn export_report(&mut self) {
+        let Some(result) = &self.result else {
+            return;
+        };
+        let stem = format!("emas-report-{}", result.seed);
+        let written: Result<Vec<String>> = ["md", "html"]
+            .iter()
+            .map(|ext| {
+                let path = std::path::PathBuf::from(format!("{stem}.{ext}"));
+                write_report(&path, &self.problem_text_cache, result)?;
+                Ok(path.display().to_string())
+            })
+            .collect();
+        self.export_message = Some(match written {
+            Ok(paths) => format!("Report saved to {}", paths.join(" and ")),
+            Err(e) => format!("Export failed: {e:#}"),
+        });
+    }
+
+    /// Opens the live output pane on the selected team, or the first team
+    /// that has streamed if the selection has not run this generation.
+    pub fn open_live_output(&mut self) {
+        let selected = self.team_scores.get(self.selected_team).map(|ts| &ts.name);
+        self.live_team = selected
+            .and_then(|name| self.live_output.iter().position(|t| &t.name == name))
+            .unwrap_or(0);
+        self.live_scroll = 0;
+        self.show_live_output = true;
     }
 
     pub fn insert_char(&mut self, c: char) {
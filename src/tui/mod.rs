@@ -13,17 +13,25 @@ use crossterm::{
 use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
-use crate::arena::{Arena, ArenaEvent, Phase};
-use crate::config::Cli;
+use crate::arena::{Arena, ArenaEvent, Phase, RunControl};
+use crate::config::TuiArgs;
+use crate::history::RunStore;
+use crate::problem::read_source;
 
 use app::{App, FieldKind, Screen, FIELD_COUNT};
 
+#[allow(clippy::large_enum_variant)]
 enum AppEvent {
     Terminal(Event),
     Arena(ArenaEvent),
 }
 
-pub async fn run_tui(cli: &Cli) -> Result<()> {
+pub async fn run_tui(args: &TuiArgs) -> Result<()> {
+    // Read a problem file or stdin up front; the form then holds the text.
+    let mut args = args.clone();
+    args.input.problem = read_source(args.input.problem.as_deref(), args.input.problem_file.as_deref())?;
+    args.input.problem_file = None;
+
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
@@ -31,7 +39,7 @@ pub async fn run_tui(cli: &Cli) -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
 
-    let mut app = App::new(cli);
+    let mut app = App::new(&args);
 
     let (tx, mut rx) = mpsc::unbounded_channel::<AppEvent>();
 
@@ -82,15 +90,34 @@ async fn event_loop(
                     app.gen_durations.clear();
                     app.gen_started_at = None;
                     app.total_tokens = 0;
+                    app.total_cost = 0.0;
+                    app.cache_hits = 0;
+                    app.cache_misses = 0;
                     app.phase = Phase::Initialising;
                     app.team_details.clear();
+                    app.islands.clear();
                     app.selected_team = 0;
                     app.show_team_detail = false;
-                    app.problem_text_cache = problem.clone();
+                    app.live_output.clear();
+                    app.show_live_output = false;
+                    app.problem_text_cache = problem.text.clone();
+                    for warning in problem.warnings {
+                        app.handle_arena_event(ArenaEvent::Warning(warning));
+                    }
+                    let problem = problem.text;
+                    let control = RunControl::new();
+                    app.control = Some(control.clone());
 
                     let event_tx = tx.clone();
                     tokio::spawn(async move {
-                        let arena = Arena::new(config);
+                        let arena = match Arena::new(config) {
+                            Ok(arena) => arena,
+                            Err(e) => {
+                                let _ = event_tx
+                                    .send(AppEvent::Arena(ArenaEvent::Error(e.to_string())));
+                                return;
+                            }
+                        };
                         let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
 
                         let bridge_tx = event_tx.clone();
@@ -102,8 +129,15 @@ async fn event_loop(
                             }
                         });
 
-                        match arena.run_with_progress(&problem, progress_tx).await {
+                        match arena.run_with_progress(&problem, progress_tx, &control).await {
                             Ok(result) => {
+                                if let Err(e) = RunStore::open_default()
+                                    .and_then(|store| store.save(&problem, &arena.config, &result))
+                                {
+                                    let _ = event_tx.send(AppEvent::Arena(ArenaEvent::Warning(
+                                        format!("Run not saved to history: {e:#}"),
+                                    )));
+                                }
                                 let _ =
                                     event_tx.send(AppEvent::Arena(ArenaEvent::Completed(result)));
                             }
@@ -148,10 +182,8 @@ async fn event_loop(
 
 fn process_event(app: &mut App, event: AppEvent) {
     match event {
-        AppEvent::Terminal(Event::Key(key)) => {
-            if key.kind == KeyEventKind::Press {
-                handle_key(app, key);
-            }
+        AppEvent::Terminal(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+            handle_key(app, key);
         }
         AppEvent::Terminal(Event::Resize(_, _)) => {}
         AppEvent::Arena(arena_event) => {
@@ -272,11 +304,44 @@ fn handle_editing_key(app: &mut App, key: event::KeyEvent) {
 }
 
 fn handle_running_key(app: &mut App, key: event::KeyEvent) {
+    if app.show_live_output {
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('l') => {
+                app.show_live_output = false;
+            }
+            KeyCode::Char('q') => {
+                app.should_quit = true;
+            }
+            KeyCode::Left | KeyCode::Char('h') if !app.live_output.is_empty() => {
+                app.live_team = app
+                    .live_team
+                    .checked_sub(1)
+                    .unwrap_or(app.live_output.len() - 1);
+                app.live_scroll = 0;
+            }
+            KeyCode::Right | KeyCode::Tab if !app.live_output.is_empty() => {
+                app.live_team = (app.live_team + 1) % app.live_output.len();
+                app.live_scroll = 0;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.live_scroll = app.live_scroll.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.live_scroll = app.live_scroll.saturating_add(1);
+            }
+            _ => {}
+        }
+        return;
+    }
+
     if app.show_team_detail {
         match key.code {
             KeyCode::Esc | KeyCode::Enter => {
                 app.show_team_detail = false;
             }
+            KeyCode::Char('l') => {
+                app.open_live_output();
+            }
             KeyCode::Char('q') => {
                 app.should_quit = true;
             }
@@ -289,21 +354,24 @@ fn handle_running_key(app: &mut App, key: event::KeyEvent) {
         KeyCode::Char('q') | KeyCode::Esc => {
             app.should_quit = true;
         }
-        KeyCode::Up | KeyCode::Char('k') => {
-            if !app.team_scores.is_empty() {
-                app.selected_team = app.selected_team.saturating_sub(1);
-            }
+        KeyCode::Up | KeyCode::Char('k') if !app.team_scores.is_empty() => {
+            app.selected_team = app.selected_team.saturating_sub(1);
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            if !app.team_scores.is_empty() {
-                app.selected_team =
-                    (app.selected_team + 1).min(app.team_scores.len().saturating_sub(1));
-            }
+        KeyCode::Down | KeyCode::Char('j') if !app.team_scores.is_empty() => {
+            app.selected_team =
+                (app.selected_team + 1).min(app.team_scores.len().saturating_sub(1));
         }
-        KeyCode::Enter => {
-            if !app.team_details.is_empty() && !app.team_scores.is_empty() {
-                app.show_team_detail = true;
-            }
+        KeyCode::Enter if !app.team_details.is_empty() && !app.team_scores.is_empty() => {
+            app.show_team_detail = true;
+        }
+        KeyCode::Char('l') => {
+            app.open_live_output();
+        }
+        KeyCode::Char('p') => {
+            app.toggle_pause();
+        }
+        KeyCode::Char('s') => {
+            app.stop_run();
         }
         _ => {}
     }
@@ -317,6 +385,9 @@ fn handle_results_key(app: &mut App, key: event::KeyEvent) {
         KeyCode::Char('n') => {
             app.reset_for_new_run();
         }
+        KeyCode::Char('e') => {
+            app.export_report();
+        }
         KeyCode::Up | KeyCode::Char('k') => {
             app.scroll_offset = app.scroll_offset.saturating_sub(1);
         }
@@ -282,11 +282,13 @@ fn draw_running(frame: &mut Frame, app: &App) {
         ])
         .split(inner);
 
-    let problem_display = if app.problem_text_cache.len() > (chunks[0].width as usize).saturating_sub(14) {
+    let first_line = app.problem_text_cache.lines().next().unwrap_or_default();
+    let multi_line = app.problem_text_cache.lines().nth(1).is_some();
+    let problem_display = if multi_line || first_line.chars().count() > (chunks[0].width as usize).saturating_sub(14) {
         let max = (chunks[0].width as usize).saturating_sub(17);
-        format!("  Problem: {}...", &app.problem_text_cache[..max.min(app.problem_text_cache.len())])
+        format!("  Problem: {}...", first_line.chars().take(max).collect::<String>())
     } else {
-        format!("  Problem: {}", app.problem_text_cache)
+        format!("  Problem: {}", first_line)
     };
     let problem_line = Paragraph::new(Line::from(vec![
         Span::styled(
@@ -328,6 +330,16 @@ fn draw_running(frame: &mut Frame, app: &App) {
     } else {
         String::new()
     };
+    let cost_str = if app.total_cost > 0.0 {
+        format!("  Cost: ${:.4}", app.total_cost)
+    } else {
+        String::new()
+    };
+    let cache_str = if app.cache_hits + app.cache_misses > 0 {
+        format!("  Cache: {}/{}", app.cache_hits, app.cache_hits + app.cache_misses)
+    } else {
+        String::new()
+    };
     let phase_str = format!("  Phase: {}", app.phase);
 
     let stats_line = Line::from(vec![
@@ -344,13 +356,44 @@ fn draw_running(frame: &mut Frame, app: &App) {
             token_str,
             Style::default().fg(Color::Cyan),
         ),
+        Span::styled(
+            cost_str,
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(
+            cache_str,
+            Style::default().fg(Color::DarkGray),
+        ),
         Span::styled(
             phase_str,
             Style::default().fg(Color::Magenta),
         ),
+        Span::styled(
+            if app.is_paused() { "  [PAUSED]" } else { "" },
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ),
     ]);
     frame.render_widget(Paragraph::new(stats_line), chunks[3]);
 
+    if !app.islands.is_empty() {
+        let mut spans = vec![Span::styled("  Islands:", Style::default().fg(Color::DarkGray))];
+        for island in &app.islands {
+            spans.push(Span::styled(
+                format!(" #{} ", island.island + 1),
+                Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(
+                format!("{} {:.2}", island.best_name, island.best_score),
+                Style::default().fg(Color::White),
+            ));
+            spans.push(Span::styled(
+                format!(" (mean {:.2}, mut {:.2}) ", island.mean_score, island.mutation_rate),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(spans)), chunks[4]);
+    }
+
     draw_team_scores(frame, app, chunks[5]);
 
     let status_line = if !app.best_name.is_empty() {
@@ -373,7 +416,10 @@ fn draw_running(frame: &mut Frame, app: &App) {
     draw_log(frame, app, chunks[7]);
 
     let help = Paragraph::new(Span::styled(
-        "  Up/Down: select team  |  Enter: team details  |  q: quit",
+        format!(
+            "  Up/Down: select team  |  Enter: team details  |  l: live output  |  p: {}  |  s: stop & synthesise  |  q: quit",
+            if app.is_paused() { "resume" } else { "pause" }
+        ),
         Style::default().fg(Color::DarkGray),
     ));
     frame.render_widget(help, chunks[8]);
@@ -381,6 +427,9 @@ fn draw_running(frame: &mut Frame, app: &App) {
     if app.show_team_detail {
         draw_team_detail_overlay(frame, app);
     }
+    if app.show_live_output {
+        draw_live_output_overlay(frame, app);
+    }
 }
 
 fn draw_team_scores(frame: &mut Frame, app: &App, area: Rect) {
@@ -510,6 +559,11 @@ fn draw_team_detail_overlay(frame: &mut Frame, app: &App) {
                 ),
             ]));
         }
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled(
+            "  l: live output  |  Esc: close",
+            Style::default().fg(Color::DarkGray),
+        )));
     } else {
         lines.push(Line::from(Span::styled(
             "  No detail available for this team.",
@@ -530,6 +584,66 @@ fn draw_team_detail_overlay(frame: &mut Frame, app: &App) {
     frame.render_widget(paragraph, overlay_area);
 }
 
+fn draw_live_output_overlay(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+
+    let overlay_w = (area.width * 80 / 100).max(40).min(area.width.saturating_sub(4));
+    let overlay_h = (area.height * 80 / 100).max(10).min(area.height.saturating_sub(2));
+    let x = area.x + (area.width.saturating_sub(overlay_w)) / 2;
+    let y = area.y + (area.height.saturating_sub(overlay_h)) / 2;
+    let overlay_area = Rect::new(x, y, overlay_w, overlay_h);
+
+    let clear = Block::default()
+        .style(Style::default().bg(Color::Black));
+    frame.render_widget(clear, overlay_area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let title = match app.live_output.get(app.live_team) {
+        Some(team) => {
+            for agent in &team.agents {
+                lines.push(Line::from(Span::styled(
+                    format!("── {} ", agent.name),
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                )));
+                for line in agent.text.lines() {
+                    lines.push(Line::raw(line));
+                }
+                lines.push(Line::raw(""));
+            }
+            format!(
+                " Live Output: {} ({}/{}) ",
+                team.name,
+                app.live_team + 1,
+                app.live_output.len(),
+            )
+        }
+        None => {
+            lines.push(Line::from(Span::styled(
+                "  No agent output has streamed this generation yet.",
+                Style::default().fg(Color::DarkGray),
+            )));
+            " Live Output ".to_string()
+        }
+    };
+
+    let block = Block::default()
+        .title(title)
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .title_bottom(Line::from(Span::styled(
+            " Left/Right: team  |  Up/Down: scroll  |  Esc: close ",
+            Style::default().fg(Color::DarkGray),
+        )))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    let paragraph = Paragraph::new(Text::from(lines))
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((app.live_scroll, 0));
+    frame.render_widget(paragraph, overlay_area);
+}
+
 fn draw_log(frame: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
         .title(" Activity Log ")
@@ -610,10 +724,20 @@ fn draw_results(frame: &mut Frame, app: &App) {
     draw_winner_info(frame, result, chunks[0]);
     draw_synthesis(frame, app, result, chunks[1]);
 
-    let help = Paragraph::new(Span::styled(
-        "  Up-Down/PgUp/PgDn: scroll  |  n: new run  |  q: quit",
-        Style::default().fg(Color::DarkGray),
-    ));
+    let help = match &app.export_message {
+        Some(msg) => Paragraph::new(Span::styled(
+            format!("  {msg}"),
+            Style::default().fg(if msg.starts_with("Export failed") {
+                Color::Red
+            } else {
+                Color::Green
+            }),
+        )),
+        None => Paragraph::new(Span::styled(
+            "  Up-Down/PgUp/PgDn: scroll  |  e: export report  |  n: new run  |  q: quit",
+            Style::default().fg(Color::DarkGray),
+        )),
+    };
     frame.render_widget(help, chunks[2]);
 }
 
@@ -635,15 +759,24 @@ fn draw_winner_info(frame: &mut Frame, result: &crate::arena::EvolutionResult, a
     lines.push(Line::from(vec![
         Span::styled(
             format!(
-                "     Generations: {}     Q:{:.1}  C:{:.1}  E:{:.1}",
+                "     Generations: {}     Q:{:.1}  C:{:.1}  E:{:.1}     Cost: ${:.4}",
                 result.generations_run,
                 result.best_score.quality,
                 result.best_score.consistency,
                 result.best_score.efficiency,
+                result.total_cost,
             ),
             Style::default().fg(Color::Gray),
         ),
     ]));
+    lines.push(Line::from(Span::styled(
+        format!("     Stopped: {}", result.stop_reason),
+        Style::default().fg(Color::Gray),
+    )));
+    lines.push(Line::from(Span::styled(
+        format!("     Seed: {}", result.seed),
+        Style::default().fg(Color::Gray),
+    )));
 
     lines.push(Line::raw(""));
     lines.push(Line::from(Span::styled(
@@ -651,11 +784,9 @@ fn draw_winner_info(frame: &mut Frame, result: &crate::arena::EvolutionResult, a
         Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
     )));
 
-    for (i, agent) in result.best_team.agents.iter().enumerate() {
-        let is_last = i == result.best_team.agents.len() - 1;
-        let branch = if is_last { "     |--" } else { "     |--" };
+    for agent in &result.best_team.agents {
         lines.push(Line::from(vec![
-            Span::styled(branch, Style::default().fg(Color::DarkGray)),
+            Span::styled("     |--", Style::default().fg(Color::DarkGray)),
             Span::styled(
                 format!(" {} ", agent.genotype.name),
                 Style::default().fg(Color::Cyan),